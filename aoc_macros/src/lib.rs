@@ -0,0 +1,165 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Fields, LitStr};
+
+// #[derive(AocParse)] with #[aoc(format = "{}: {}")] implements FromStr, filling the
+// struct's fields in order from each {} using aoc2024::aoc::scan
+#[proc_macro_derive(AocParse, attributes(aoc))]
+pub fn derive_aoc_parse(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn format_attr(input: &DeriveInput) -> syn::Result<LitStr> {
+    let mut format = None;
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("aoc")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("format") {
+                format = Some(meta.value()?.parse::<LitStr>()?);
+                Ok(())
+            } else {
+                Err(meta.error("expected `format = \"...\"`"))
+            }
+        })?;
+    }
+    format.ok_or_else(|| {
+        syn::Error::new_spanned(
+            &input.ident,
+            "AocParse needs a #[aoc(format = \"...\")] attribute",
+        )
+    })
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let format = format_attr(input)?;
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "AocParse only works on structs",
+        ));
+    };
+
+    let placeholders = format.value().matches("{}").count();
+    if placeholders != data.fields.len() {
+        return Err(syn::Error::new_spanned(
+            &format,
+            format!(
+                "format has {placeholders} {{}} but the struct has {} field{}",
+                data.fields.len(),
+                if data.fields.len() == 1 { "" } else { "s" }
+            ),
+        ));
+    }
+
+    if format.value().contains("{}{}") {
+        return Err(syn::Error::new_spanned(
+            &format,
+            "format has two {} with nothing between them, so there's no telling where the first ends",
+        ));
+    }
+
+    let scan = quote!(::aoc2024::aoc::scan);
+    let values = data.fields.iter().map(|field| {
+        let ty = &field.ty;
+        quote!(#scan::scan_field::<#ty>(s, parts.next().unwrap())?)
+    });
+    let body = match &data.fields {
+        Fields::Named(fields) => {
+            let names = fields.named.iter().map(|f| f.ident.as_ref().unwrap());
+            quote!(Self { #(#names: #values),* })
+        }
+        Fields::Unnamed(_) => quote!(Self(#(#values),*)),
+        Fields::Unit => quote!(Self),
+    };
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::std::str::FromStr for #name #ty_generics #where_clause {
+            type Err = #scan::ScanError;
+
+            #[allow(unused_mut, unused_variables)]
+            fn from_str(s: &str) -> ::std::result::Result<Self, Self::Err> {
+                let mut parts = #scan::split_template(#format, s)?.into_iter();
+                Ok(#body)
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use syn::parse_quote;
+
+    fn error(input: DeriveInput) -> String {
+        expand(&input).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            error(parse_quote!(
+                struct Rule(u8);
+            )),
+            "AocParse needs a #[aoc(format = \"...\")] attribute"
+        );
+        assert_eq!(
+            error(parse_quote!(
+                #[aoc(fmt = "{}")]
+                struct Rule(u8);
+            )),
+            "expected `format = \"...\"`"
+        );
+        assert_eq!(
+            error(parse_quote!(
+                #[aoc(format = 1)]
+                struct Rule(u8);
+            )),
+            "expected string literal"
+        );
+        assert_eq!(
+            error(parse_quote!(
+                #[aoc(format = "{}")]
+                enum Rule {
+                    A,
+                }
+            )),
+            "AocParse only works on structs"
+        );
+        assert_eq!(
+            error(parse_quote!(
+                #[aoc(format = "{}|{}")]
+                struct Rule(u8);
+            )),
+            "format has 2 {} but the struct has 1 field"
+        );
+        assert_eq!(
+            error(parse_quote!(
+                #[aoc(format = "{}{}")]
+                struct Rule(u8, u8);
+            )),
+            "format has two {} with nothing between them, so there's no telling where the first ends"
+        );
+    }
+
+    #[test]
+    fn test_expand() {
+        let tokens = expand(&parse_quote!(
+            #[aoc(format = "{}|{}")]
+            struct Rule {
+                before: u8,
+                after: u8,
+            }
+        ))
+        .unwrap()
+        .to_string();
+
+        assert!(tokens.contains("impl :: std :: str :: FromStr for Rule"));
+        assert!(tokens.contains("split_template (\"{}|{}\" , s)"));
+        assert!(tokens.contains("before : :: aoc2024 :: aoc :: scan :: scan_field :: < u8 >"));
+    }
+}
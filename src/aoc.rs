@@ -1,5 +1,16 @@
 pub mod algo;
+pub mod bytes;
 pub mod cli;
 pub mod example;
+pub mod expr;
+pub mod geometry;
+pub mod graph;
 pub mod grid;
+pub mod input;
+pub mod interval;
+pub mod linalg;
 pub mod parse;
+pub mod player;
+pub mod render;
+pub mod scan;
+pub mod vm;
@@ -0,0 +1,244 @@
+use std::{fs::File, io, ops::Deref, path::Path};
+
+use super::{grid::Grid, input::InputError};
+use crate::quant::Integer;
+
+enum Buffer {
+    Owned(Vec<u8>),
+    #[cfg(feature = "mmap")]
+    Mapped(memmap2::Mmap),
+}
+
+// The whole input as one byte buffer, mapped straight from the file with the mmap feature
+pub struct Bytes {
+    buffer: Buffer,
+}
+
+impl Bytes {
+    pub fn read(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = File::open(path)?;
+        Self::read_file(&file)
+    }
+
+    #[cfg(feature = "mmap")]
+    fn read_file(file: &File) -> io::Result<Self> {
+        // Empty files can't be mapped
+        if file.metadata()?.len() == 0 {
+            return Ok(Self::from(vec![]));
+        }
+        // Safety: inputs aren't modified while a day is running
+        let map = unsafe { memmap2::Mmap::map(file)? };
+        Ok(Self {
+            buffer: Buffer::Mapped(map),
+        })
+    }
+
+    #[cfg(not(feature = "mmap"))]
+    fn read_file(mut file: &File) -> io::Result<Self> {
+        let mut bytes = vec![];
+        io::Read::read_to_end(&mut file, &mut bytes)?;
+        Ok(Self::from(bytes))
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        match &self.buffer {
+            Buffer::Owned(bytes) => bytes,
+            #[cfg(feature = "mmap")]
+            Buffer::Mapped(map) => map,
+        }
+    }
+}
+
+impl From<Vec<u8>> for Bytes {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self {
+            buffer: Buffer::Owned(bytes),
+        }
+    }
+}
+
+impl Deref for Bytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+// Lines without their \n or \r\n, and no empty line after a final newline
+pub fn lines(bytes: &[u8]) -> impl Iterator<Item = &[u8]> {
+    let bytes = bytes.strip_suffix(b"\n").unwrap_or(bytes);
+    let mut lines = bytes.split(|&b| b == b'\n');
+    if bytes.is_empty() {
+        lines.next();
+    }
+    lines.map(|line| line.strip_suffix(b"\r").unwrap_or(line))
+}
+
+// Every integer in bytes, with a - directly before the digits making it negative whatever T is.
+// Panics if one doesn't fit in T, which for an unsigned T includes any negative number.
+pub fn numbers<T: Integer>(bytes: &[u8]) -> Numbers<'_, T> {
+    Numbers {
+        bytes,
+        pos: 0,
+        digits: digit_values(),
+    }
+}
+
+fn digit_values<T: Integer>() -> [T; 10] {
+    let mut digits = [T::ZERO; 10];
+    for i in 1..10 {
+        digits[i] = digits[i - 1] + T::ONE;
+    }
+    digits
+}
+
+pub struct Numbers<'a, T> {
+    bytes: &'a [u8],
+    pos: usize,
+    digits: [T; 10],
+}
+
+impl<T: Integer> Iterator for Numbers<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        let bytes = self.bytes;
+        let start = self.pos + bytes[self.pos..].iter().position(u8::is_ascii_digit)?;
+        let negative = start > 0 && bytes[start - 1] == b'-';
+
+        let ten = self.digits[9] + T::ONE;
+        let mut n = T::ZERO;
+        let mut pos = start;
+        while let Some(&b) = bytes.get(pos).filter(|b| b.is_ascii_digit()) {
+            let digit = self.digits[(b - b'0') as usize];
+            // Negative numbers build downwards so that MIN fits, and an unsigned T can't go below 0
+            n = n
+                .checked_mul(ten)
+                .and_then(|n| {
+                    if negative {
+                        n.checked_sub(digit)
+                    } else {
+                        n.checked_add(digit)
+                    }
+                })
+                .unwrap_or_else(|| {
+                    let sign = start - negative as usize;
+                    let end = bytes[start..]
+                        .iter()
+                        .position(|b| !b.is_ascii_digit())
+                        .map_or(bytes.len(), |len| start + len);
+                    panic!(
+                        "{} doesn't fit in the number type",
+                        String::from_utf8_lossy(&bytes[sign..end])
+                    )
+                });
+            pos += 1;
+        }
+
+        self.pos = pos;
+        Some(n)
+    }
+}
+
+// A whole slice such as b"-12" as a number, None if there's anything else in it
+pub fn parse_int<T: Integer>(bytes: &[u8]) -> Option<T> {
+    let digits = bytes.strip_prefix(b"-").filter(|_| T::SIGNED);
+    let unsigned = digits.unwrap_or(bytes);
+    if unsigned.is_empty() || !unsigned.iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+    let values = digit_values::<T>();
+    let ten = values[9] + T::ONE;
+    unsigned.iter().try_fold(T::ZERO, |n, &b| {
+        let digit = values[(b - b'0') as usize];
+        if digits.is_some() {
+            n.checked_mul(ten)?.checked_sub(digit)
+        } else {
+            n.checked_mul(ten)?.checked_add(digit)
+        }
+    })
+}
+
+// One cell per byte, all rows must be the same width
+pub fn grid(bytes: &[u8]) -> Result<Grid<Vec<u8>>, InputError> {
+    grid_map(bytes, |b| b)
+}
+
+pub fn grid_map<U>(
+    bytes: &[u8],
+    mut cell: impl FnMut(u8) -> U,
+) -> Result<Grid<Vec<U>>, InputError> {
+    let width = lines(bytes).next().map_or(0, <[u8]>::len);
+    let mut items = Vec::with_capacity(bytes.len());
+    let mut height = 0;
+
+    for line in lines(bytes) {
+        if line.len() != width {
+            return Err(InputError {
+                line: height + 1,
+                column: 1,
+                message: format!("row is {} wide, expected {width}", line.len()),
+            });
+        }
+        items.extend(line.iter().map(|&b| cell(b)));
+        height += 1;
+    }
+
+    Ok(Grid::new(width, height, items))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_lines() {
+        let bytes = Bytes::from(b"ab\r\n\r\ncd\n".to_vec());
+        assert_eq!(
+            lines(&bytes).collect::<Vec<_>>(),
+            vec![&b"ab"[..], b"", b"cd"]
+        );
+        assert_eq!(lines(b"").count(), 0);
+    }
+
+    #[test]
+    fn test_numbers() {
+        let bytes = b"p=0,4 v=3,-3\nButton A: X+94, Y-128";
+        assert_eq!(
+            numbers::<i64>(bytes).collect::<Vec<_>>(),
+            vec![0, 4, 3, -3, 94, -128]
+        );
+        assert_eq!(
+            numbers::<u8>(b"x=0, y=255, 1 - 2").collect::<Vec<_>>(),
+            vec![0, 255, 1, 2]
+        );
+        assert_eq!(numbers::<i8>(b"-128").next(), Some(-128));
+        assert_eq!(numbers::<u8>(b"-0").next(), Some(0));
+
+        let panic = |bytes: &'static [u8]| {
+            let err = std::panic::catch_unwind(|| numbers::<u8>(bytes).count()).unwrap_err();
+            err.downcast::<String>().unwrap()
+        };
+        assert_eq!(*panic(b"v=3,-3 "), "-3 doesn't fit in the number type");
+        assert_eq!(*panic(b"256\n"), "256 doesn't fit in the number type");
+
+        assert_eq!(parse_int::<i32>(b"-42"), Some(-42));
+        assert_eq!(parse_int::<u32>(b"-42"), None);
+        assert_eq!(parse_int::<u8>(b"256"), None);
+    }
+
+    #[test]
+    fn test_grid() {
+        let digits = grid_map(b"0123\r\n4567\r\n", |b| b - b'0').unwrap();
+        assert_eq!((digits.width(), digits.height()), (4, 2));
+        assert_eq!(*digits.value_at(1, 1).unwrap(), 5);
+
+        assert_eq!(grid(b"ab\nc").err().unwrap().line, 2);
+
+        // Trailing spaces are cells, only the final newline goes
+        let spaces = grid(b"a \n  \r\n").unwrap();
+        assert_eq!((spaces.width(), spaces.height()), (2, 2));
+        assert_eq!(*spaces.value_at(1, 1).unwrap(), b' ');
+    }
+}
@@ -6,9 +6,14 @@ use std::{
 
 use clap::Parser;
 
+use super::{bytes::Bytes, input::Input};
+
 #[derive(Parser, Debug)]
 pub struct Cli {
     pub input: Option<std::path::PathBuf>,
+
+    #[arg(long)]
+    pub play: bool,
 }
 
 fn input_path(filename: &str) -> std::path::PathBuf {
@@ -30,12 +35,20 @@ impl Cli {
         read_to_string(self.input_file()).unwrap()
     }
 
+    pub fn input(&self) -> Input {
+        Input::new(&self.input_string())
+    }
+
+    pub fn input_bytes(&self) -> Bytes {
+        Bytes::read(self.input_file()).unwrap()
+    }
+
     pub fn input_file(&self) -> std::path::PathBuf {
         if let Some(f) = &self.input {
             f.clone()
         } else {
             let day_name = env::args()
-                .last()
+                .next()
                 .unwrap()
                 .rsplit_once('/')
                 .unwrap()
@@ -3,6 +3,8 @@ use std::{
     io::{BufRead, BufReader},
 };
 
+use super::{bytes::Bytes, input::Input};
+
 fn example_path(example: &str) -> std::path::PathBuf {
     std::path::Path::new(file!())
         .parent()
@@ -21,3 +23,11 @@ pub fn example_lines(example: &str) -> impl Iterator<Item = String> {
 pub fn example_string(example: &str) -> String {
     read_to_string(example_path(example)).unwrap()
 }
+
+pub fn example_input(example: &str) -> Input {
+    Input::new(&example_string(example))
+}
+
+pub fn example_bytes(example: &str) -> Bytes {
+    Bytes::read(example_path(example)).unwrap()
+}
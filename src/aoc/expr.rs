@@ -0,0 +1,412 @@
+use std::fmt::Display;
+
+use crate::quant::digit_count;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExprError {
+    // Byte offset into the expression, None for errors while evaluating
+    pub position: Option<usize>,
+    pub message: String,
+}
+
+impl Display for ExprError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.position {
+            Some(position) => write!(f, "Expression error at {position}: {}", self.message),
+            None => write!(f, "Expression error: {}", self.message),
+        }
+    }
+}
+
+impl std::error::Error for ExprError {}
+
+fn error<T>(position: usize, message: String) -> Result<T, ExprError> {
+    Err(ExprError {
+        position: Some(position),
+        message,
+    })
+}
+
+fn eval_error<T>(message: String) -> Result<T, ExprError> {
+    Err(ExprError {
+        position: None,
+        message,
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Assoc {
+    Left,
+    Right,
+}
+
+#[derive(Debug, Clone)]
+pub struct BinaryOp {
+    pub symbol: String,
+    // Higher binds tighter
+    pub precedence: u8,
+    pub assoc: Assoc,
+    // None on overflow or anything else without an answer
+    pub apply: fn(i64, i64) -> Option<i64>,
+}
+
+// The digits of a followed by those of b, as in 12 || 345 = 12345
+pub fn concat(a: i64, b: i64) -> Option<i64> {
+    if b < 0 {
+        return None;
+    }
+    a.checked_mul(10i64.checked_pow(digit_count(b))?)?
+        .checked_add(b)
+}
+
+// Which binary operators an expression can use and how tightly each binds
+#[derive(Debug, Clone, Default)]
+pub struct Operators {
+    ops: Vec<BinaryOp>,
+    // How tightly unary minus binds, None for tighter than any binary operator so that
+    // -2 ^ 2 is 4. Only available when "-" is a binary operator.
+    neg_precedence: Option<u8>,
+}
+
+impl Operators {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Adds an operator, replacing any with the same symbol. Panics on symbols the tokenizer
+    // couldn't pick out, which are empty ones and ones starting with a digit, whitespace or
+    // a bracket.
+    pub fn op(
+        mut self,
+        symbol: &str,
+        precedence: u8,
+        assoc: Assoc,
+        apply: fn(i64, i64) -> Option<i64>,
+    ) -> Self {
+        assert!(
+            symbol.starts_with(|c: char| {
+                !(c.is_ascii_digit() || c.is_whitespace() || c == '(' || c == ')')
+            }),
+            "{symbol:?} can't be an operator symbol"
+        );
+        self.ops.retain(|op| op.symbol != symbol);
+        self.ops.push(BinaryOp {
+            symbol: symbol.to_string(),
+            precedence,
+            assoc,
+            apply,
+        });
+        self
+    }
+
+    // Unary minus binds looser than binary operators with a higher precedence, and tighter
+    // than those with the same or lower, so with neg(2) -2 ^ 3 * 4 is (-(2 ^ 3)) * 4 and
+    // -2 * 3 is (-2) * 3
+    pub fn neg(mut self, precedence: u8) -> Self {
+        self.neg_precedence = Some(precedence);
+        self
+    }
+
+    // School rules, * and / before + and -
+    pub fn standard() -> Self {
+        Self::new()
+            .op("+", 1, Assoc::Left, i64::checked_add)
+            .op("-", 1, Assoc::Left, i64::checked_sub)
+            .op("*", 2, Assoc::Left, i64::checked_mul)
+            .op("/", 2, Assoc::Left, i64::checked_div)
+    }
+
+    // Everything strictly left to right
+    pub fn same_precedence() -> Self {
+        Self::new()
+            .op("+", 1, Assoc::Left, i64::checked_add)
+            .op("-", 1, Assoc::Left, i64::checked_sub)
+            .op("*", 1, Assoc::Left, i64::checked_mul)
+            .op("/", 1, Assoc::Left, i64::checked_div)
+    }
+
+    // + before *, the other way round to usual
+    pub fn add_first() -> Self {
+        Self::new().op("+", 2, Assoc::Left, i64::checked_add).op(
+            "*",
+            1,
+            Assoc::Left,
+            i64::checked_mul,
+        )
+    }
+
+    pub fn get(&self, symbol: &str) -> Option<&BinaryOp> {
+        self.ops.iter().find(|op| op.symbol == symbol)
+    }
+
+    // The longest operator symbol that s starts with
+    fn longest_prefix(&self, s: &str) -> Option<&BinaryOp> {
+        self.ops
+            .iter()
+            .filter(|op| s.starts_with(&op.symbol))
+            .max_by_key(|op| op.symbol.len())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Token {
+    Number(i64),
+    Op(String),
+    Open,
+    Close,
+}
+
+// Tokens along with their byte offsets. Operators are matched longest first, so "||" isn't
+// read as two "|".
+pub fn tokenize(input: &str, ops: &Operators) -> Result<Vec<(usize, Token)>, ExprError> {
+    let mut out = vec![];
+    let mut pos = 0;
+
+    while let Some(c) = input[pos..].chars().next() {
+        let rest = &input[pos..];
+        if c.is_whitespace() {
+            pos += c.len_utf8();
+        } else if c.is_ascii_digit() {
+            let len = rest
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(rest.len());
+            let Ok(n) = rest[..len].parse() else {
+                return error(pos, format!("{} is too large", &rest[..len]));
+            };
+            out.push((pos, Token::Number(n)));
+            pos += len;
+        } else if c == '(' || c == ')' {
+            out.push((pos, if c == '(' { Token::Open } else { Token::Close }));
+            pos += 1;
+        } else if let Some(op) = ops.longest_prefix(rest) {
+            out.push((pos, Token::Op(op.symbol.clone())));
+            pos += op.symbol.len();
+        } else {
+            return error(pos, format!("Unexpected {c:?}"));
+        }
+    }
+    Ok(out)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    Number(i64),
+    Neg(Box<Expr>),
+    Binary {
+        op: String,
+        lhs: Box<Expr>,
+        rhs: Box<Expr>,
+    },
+}
+
+impl Expr {
+    pub fn eval(&self, ops: &Operators) -> Result<i64, ExprError> {
+        match self {
+            Expr::Number(n) => Ok(*n),
+            Expr::Neg(e) => {
+                let n = e.eval(ops)?;
+                n.checked_neg()
+                    .map_or_else(|| eval_error(format!("-{n} overflows")), Ok)
+            }
+            Expr::Binary { op, lhs, rhs } => {
+                let Some(binary) = ops.get(op) else {
+                    return eval_error(format!("Unknown operator {op:?}"));
+                };
+                let (a, b) = (lhs.eval(ops)?, rhs.eval(ops)?);
+                (binary.apply)(a, b)
+                    .map_or_else(|| eval_error(format!("{a} {op} {b} has no answer")), Ok)
+            }
+        }
+    }
+
+    fn fmt_operand(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Expr::Number(n) if *n >= 0 => write!(f, "{self}"),
+            _ => write!(f, "({self})"),
+        }
+    }
+}
+
+// Parenthesises every nested operation, negation and negative numbers included, so it parses
+// back to the same value whatever the precedence
+impl Display for Expr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Expr::Number(n) => write!(f, "{n}"),
+            Expr::Neg(e) => {
+                f.write_str("-")?;
+                e.fmt_operand(f)
+            }
+            Expr::Binary { op, lhs, rhs } => {
+                lhs.fmt_operand(f)?;
+                write!(f, " {op} ")?;
+                rhs.fmt_operand(f)
+            }
+        }
+    }
+}
+
+struct Parser<'a> {
+    tokens: Vec<(usize, Token)>,
+    pos: usize,
+    end: usize,
+    ops: &'a Operators,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(_, t)| t)
+    }
+
+    fn offset(&self) -> usize {
+        self.tokens.get(self.pos).map_or(self.end, |(i, _)| *i)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.peek().cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn atom(&mut self) -> Result<Expr, ExprError> {
+        let offset = self.offset();
+        match self.next() {
+            Some(Token::Number(n)) => Ok(Expr::Number(n)),
+            Some(Token::Op(op)) if op == "-" => {
+                let min_bp = self
+                    .ops
+                    .neg_precedence
+                    .map_or(u16::MAX, |precedence| precedence as u16 * 2 + 2);
+                Ok(Expr::Neg(Box::new(self.expr(min_bp)?)))
+            }
+            Some(Token::Open) => {
+                let e = self.expr(0)?;
+                match self.next() {
+                    Some(Token::Close) => Ok(e),
+                    _ => error(offset, "Unclosed (".to_string()),
+                }
+            }
+            Some(token) => error(offset, format!("Expected a number, found {token:?}")),
+            None => error(offset, "Expected a number, found the end".to_string()),
+        }
+    }
+
+    // Pratt parsing, where each operator binds its left at 2 * precedence + 1, and its right
+    // one above that for left associative operators or the same for right
+    fn expr(&mut self, min_bp: u16) -> Result<Expr, ExprError> {
+        let mut lhs = self.atom()?;
+
+        while let Some(Token::Op(symbol)) = self.peek() {
+            let op = self.ops.get(symbol).unwrap();
+            let left_bp = op.precedence as u16 * 2 + 1;
+            let right_bp = match op.assoc {
+                Assoc::Left => left_bp + 1,
+                Assoc::Right => left_bp,
+            };
+            if left_bp < min_bp {
+                break;
+            }
+
+            let op = op.symbol.clone();
+            self.pos += 1;
+            let rhs = self.expr(right_bp)?;
+            lhs = Expr::Binary {
+                op,
+                lhs: Box::new(lhs),
+                rhs: Box::new(rhs),
+            };
+        }
+        Ok(lhs)
+    }
+}
+
+pub fn parse(input: &str, ops: &Operators) -> Result<Expr, ExprError> {
+    let mut parser = Parser {
+        tokens: tokenize(input, ops)?,
+        pos: 0,
+        end: input.len(),
+        ops,
+    };
+    let expr = parser.expr(0)?;
+    match parser.peek() {
+        None => Ok(expr),
+        Some(token) => error(parser.offset(), format!("Unexpected {token:?}")),
+    }
+}
+
+pub fn eval(input: &str, ops: &Operators) -> Result<i64, ExprError> {
+    parse(input, ops)?.eval(ops)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_precedence() {
+        let input = "1 + 2 * 3 + 4 * 5 + 6";
+        assert_eq!(eval(input, &Operators::standard()), Ok(33));
+        assert_eq!(eval(input, &Operators::same_precedence()), Ok(71));
+        assert_eq!(eval(input, &Operators::add_first()), Ok(231));
+
+        assert_eq!(eval("2 * (3 + -4) - -1", &Operators::standard()), Ok(-1));
+
+        let power = Operators::standard().op("^", 3, Assoc::Right, |a, b| {
+            a.checked_pow(b.try_into().ok()?)
+        });
+        assert_eq!(eval("2 ^ 3 ^ 2", &power), Ok(512));
+        assert_eq!(eval("-2 ^ 2", &power), Ok(4));
+        assert_eq!(eval("-2 ^ 2", &power.clone().neg(2)), Ok(-4));
+        let expr = parse("-2 ^ 3 * 4", &power.clone().neg(2)).unwrap();
+        assert_eq!(expr.to_string(), "(-(2 ^ 3)) * 4");
+        assert_eq!(parse(&expr.to_string(), &power.clone().neg(0)), Ok(expr));
+
+        let same = Operators::standard().neg(2);
+        assert_eq!(parse("-2 * 3", &same), parse("(-2) * 3", &same));
+        assert_eq!(parse("-2 * 3", &same).unwrap().to_string(), "(-2) * 3");
+        assert_eq!(eval("-2 + 3", &Operators::standard().neg(1)), Ok(1));
+
+        let day7 = Operators::same_precedence().op("||", 1, Assoc::Left, concat);
+        assert_eq!(eval("6 * 8 || 6 * 15", &day7), Ok(7290));
+    }
+
+    #[test]
+    fn test_print_back() {
+        let ops = Operators::add_first();
+        let expr = parse("2 * 3 + (4 * 5)", &ops).unwrap();
+        assert_eq!(expr.to_string(), "2 * (3 + (4 * 5))");
+        assert_eq!(parse(&expr.to_string(), &Operators::standard()), Ok(expr));
+
+        let power = Operators::new()
+            .op("-", 1, Assoc::Left, i64::checked_sub)
+            .op("^", 3, Assoc::Right, |a, b| {
+                a.checked_pow(b.try_into().ok()?)
+            })
+            .neg(2);
+        let expr = Expr::Binary {
+            op: "^".to_string(),
+            lhs: Box::new(Expr::Number(-3)),
+            rhs: Box::new(Expr::Number(2)),
+        };
+        assert_eq!(expr.to_string(), "(-3) ^ 2");
+        assert_eq!(eval(&expr.to_string(), &power), expr.eval(&power));
+    }
+
+    #[test]
+    fn test_errors() {
+        let ops = Operators::standard();
+        assert_eq!(eval("1 + ", &ops).unwrap_err().position, Some(4));
+        assert_eq!(eval("(1 + 2", &ops).unwrap_err().position, Some(0));
+        assert_eq!(eval("1 % 2", &ops).unwrap_err().position, Some(2));
+        assert_eq!(eval("1 2", &ops).unwrap_err().position, Some(2));
+
+        let err = eval("1 / 0", &ops).unwrap_err();
+        assert_eq!(err.position, None);
+        assert_eq!(err.to_string(), "Expression error: 1 / 0 has no answer");
+
+        for symbol in ["", "1+", " +", "(", ")"] {
+            let add = || Operators::new().op(symbol, 1, Assoc::Left, i64::checked_add);
+            assert!(std::panic::catch_unwind(add).is_err());
+        }
+    }
+}
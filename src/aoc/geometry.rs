@@ -0,0 +1,587 @@
+use std::{
+    cmp::Ordering,
+    collections::{HashMap, HashSet, VecDeque},
+    ops::Index,
+};
+
+use crate::{
+    point2d::{Point2D, Point2Disize},
+    quant::{gcd, Integer, Signed},
+};
+
+use super::grid::{bitgrid::PointSet, Direction, Grid, Point};
+
+fn neighbours(point2d: &Point2Disize) -> [Point2Disize; 4] {
+    [
+        *point2d + (-1, 0),
+        *point2d + (1, 0),
+        *point2d + (0, -1),
+        *point2d + (0, 1),
+    ]
+}
+
+// One unit of boundary between a cell in the shape and one outside it, (x, y) is the cell
+// corner it starts from
+#[derive(Debug, Clone, Copy)]
+struct Edge {
+    direction: Direction,
+    x: isize,
+    y: isize,
+}
+
+impl Ord for Edge {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match self.direction.cmp(&other.direction) {
+            Ordering::Equal => {}
+            ord => return ord,
+        }
+        match self.direction {
+            Direction::Up | Direction::Down => {
+                match self.y.cmp(&other.y) {
+                    Ordering::Equal => {}
+                    ord => return ord,
+                }
+                self.x.cmp(&other.x)
+            }
+            Direction::Left | Direction::Right => {
+                match self.x.cmp(&other.x) {
+                    Ordering::Equal => {}
+                    ord => return ord,
+                }
+                self.y.cmp(&other.y)
+            }
+        }
+    }
+}
+
+impl PartialOrd for Edge {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Edge {
+    fn eq(&self, other: &Self) -> bool {
+        self.direction == other.direction && self.x == other.x && self.y == other.y
+    }
+}
+
+impl Eq for Edge {}
+
+impl Edge {
+    pub fn new(direction: Direction, x: isize, y: isize) -> Self {
+        Self { direction, x, y }
+    }
+
+    pub fn connects(&self, &Self { direction, x, y }: &Self) -> bool {
+        self.direction == direction
+            && match self.direction {
+                Direction::Left | Direction::Right => {
+                    self.x == x && self.y >= y - 1 && self.y <= y + 1
+                }
+                Direction::Up | Direction::Down => {
+                    self.y == y && self.x >= x - 1 && self.x <= x + 1
+                }
+            }
+    }
+}
+
+// Loops of cell corners, outer boundaries run clockwise on screen and holes anticlockwise
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Boundaries {
+    pub outer: Vec<Vec<Point2Disize>>,
+    pub holes: Vec<Vec<Point2Disize>>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Shape {
+    points: HashSet<Point2Disize>,
+}
+
+impl Shape {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn from_points<'a, I>(points: I) -> Self
+    where
+        I: IntoIterator<Item = &'a Point>,
+    {
+        points.into_iter().map(|&p| Point2Disize::from(p)).collect()
+    }
+
+    pub fn add_point(&mut self, point: Point2Disize) {
+        self.points.insert(point);
+    }
+
+    pub fn contains(&self, point: &Point2Disize) -> bool {
+        self.points.contains(point)
+    }
+
+    pub fn points(&self) -> impl Iterator<Item = &Point2Disize> + '_ {
+        self.points.iter()
+    }
+
+    // Any point in the shape, for looking up what the shape was made of
+    pub fn any_point(&self) -> Option<Point2Disize> {
+        self.points.iter().next().copied()
+    }
+
+    pub fn area(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn perimeter(&self) -> usize {
+        self.points
+            .iter()
+            .flat_map(neighbours)
+            .filter(|p| !self.points.contains(p))
+            .count()
+    }
+
+    fn edges(&self) -> Vec<Edge> {
+        let mut edges: Vec<_> = vec![];
+
+        for &p in &self.points {
+            if !self.points.contains(&(p - (1, 0))) {
+                edges.push(Edge::new(Direction::Left, p.x, p.y));
+            }
+            if !self.points.contains(&(p + (1, 0))) {
+                edges.push(Edge::new(Direction::Right, p.x + 1, p.y));
+            }
+
+            if !self.points.contains(&(p - (0, 1))) {
+                edges.push(Edge::new(Direction::Up, p.x, p.y));
+            }
+            if !self.points.contains(&(p + (0, 1))) {
+                edges.push(Edge::new(Direction::Down, p.x, p.y + 1));
+            }
+        }
+
+        edges
+    }
+
+    pub fn sides(&self) -> usize {
+        let mut edges = self.edges();
+
+        edges.sort();
+        let mut count = 0;
+        let mut last_edge: Option<Edge> = None;
+
+        for edge in edges {
+            let Some(le) = last_edge else {
+                last_edge = Some(edge);
+                count = 1;
+                continue;
+            };
+
+            if !edge.connects(&le) {
+                count += 1;
+            }
+
+            last_edge = Some(edge);
+        }
+
+        count
+    }
+
+    // Every corner turns the boundary, so this matches sides() but without sorting
+    pub fn corners(&self) -> usize {
+        let has = |p: Point2Disize| self.points.contains(&p);
+        self.points
+            .iter()
+            .map(|&p| {
+                [(-1, -1), (1, -1), (1, 1), (-1, 1)]
+                    .into_iter()
+                    .filter(|&(dx, dy)| {
+                        let horizontal = has(p + (dx, 0));
+                        let vertical = has(p + (0, dy));
+                        let diagonal = has(p + (dx, dy));
+                        (!horizontal && !vertical) || (horizontal && vertical && !diagonal)
+                    })
+                    .count()
+            })
+            .sum()
+    }
+
+    // Inclusive (min, max) corners
+    pub fn bounding_box(&self) -> Option<(Point2Disize, Point2Disize)> {
+        let first = self.any_point()?;
+        Some(self.points.iter().fold((first, first), |(min, max), p| {
+            (
+                Point2Disize::new(min.x.min(p.x), min.y.min(p.y)),
+                Point2Disize::new(max.x.max(p.x), max.y.max(p.y)),
+            )
+        }))
+    }
+
+    pub fn centroid(&self) -> Option<(f64, f64)> {
+        if self.points.is_empty() {
+            return None;
+        }
+        let n = self.points.len() as f64;
+        let (x, y) = self
+            .points
+            .iter()
+            .fold((0.0, 0.0), |(x, y), p| (x + p.x as f64, y + p.y as f64));
+        Some((x / n, y / n))
+    }
+
+    pub fn boundaries(&self) -> Boundaries {
+        // Walk each cell's missing sides keeping the shape on the right
+        let mut outgoing: HashMap<Point2Disize, Vec<Point2Disize>> = HashMap::new();
+        for edge in self.edges() {
+            let (from, to) = match edge.direction {
+                Direction::Up => ((edge.x, edge.y), (edge.x + 1, edge.y)),
+                Direction::Right => ((edge.x, edge.y), (edge.x, edge.y + 1)),
+                Direction::Down => ((edge.x + 1, edge.y), (edge.x, edge.y)),
+                Direction::Left => ((edge.x, edge.y + 1), (edge.x, edge.y)),
+            };
+            outgoing.entry(from.into()).or_default().push(to.into());
+        }
+
+        let mut starts: Vec<_> = outgoing.keys().copied().collect();
+        starts.sort_by_key(|p| (p.y, p.x));
+
+        let mut boundaries = Boundaries::default();
+        for start in starts {
+            let mut from = start;
+            let mut loop_points = vec![];
+            let mut last: Option<Point2Disize> = None;
+
+            while let Some(to) = take_next(&mut outgoing, from, last) {
+                loop_points.push(from);
+                last = Some(to - from);
+                from = to;
+                if from == start {
+                    break;
+                }
+            }
+
+            if loop_points.is_empty() {
+                continue;
+            }
+
+            let loop_points = simplify(loop_points);
+            if twice_signed_area(&loop_points) > 0 {
+                boundaries.outer.push(loop_points);
+            } else {
+                boundaries.holes.push(loop_points);
+            }
+        }
+        boundaries
+    }
+}
+
+// Where a loop touches itself diagonally, prefer turning right so each loop stays tight
+fn take_next(
+    outgoing: &mut HashMap<Point2Disize, Vec<Point2Disize>>,
+    from: Point2Disize,
+    last: Option<Point2Disize>,
+) -> Option<Point2Disize> {
+    let options = outgoing.get_mut(&from)?;
+    let i = match last {
+        Some(d) if options.len() > 1 => options
+            .iter()
+            .position(|&to| to - from == d.rotate90())
+            .unwrap_or(0),
+        _ => 0,
+    };
+    let next = options.swap_remove(i);
+    if options.is_empty() {
+        outgoing.remove(&from);
+    }
+    Some(next)
+}
+
+fn simplify(points: Vec<Point2Disize>) -> Vec<Point2Disize> {
+    let n = points.len();
+    (0..n)
+        .filter(|&i| {
+            let prev = points[(i + n - 1) % n];
+            let next = points[(i + 1) % n];
+            let a = points[i] - prev;
+            let b = next - points[i];
+            a.x * b.y - a.y * b.x != 0
+        })
+        .map(|i| points[i])
+        .collect()
+}
+
+impl FromIterator<Point2Disize> for Shape {
+    fn from_iter<I: IntoIterator<Item = Point2Disize>>(iter: I) -> Self {
+        Self {
+            points: iter.into_iter().collect(),
+        }
+    }
+}
+
+// Connected groups of equal values
+pub fn regions<T>(grid: &Grid<T>) -> Vec<Shape>
+where
+    T: Index<usize>,
+    T::Output: PartialEq,
+{
+    let mut collected = PointSet::for_grid(grid);
+    let mut regions = vec![];
+
+    for cell in grid.iter() {
+        let p = cell.point();
+
+        if collected.contains(&p) {
+            continue;
+        }
+        collected.insert(p);
+
+        let mut queue = VecDeque::new();
+        queue.push_back(cell);
+
+        let mut region = Shape::new();
+        region.add_point(Point2Disize::from(p));
+
+        while let Some(cell) = queue.pop_front() {
+            for direction in Direction::all() {
+                let Some(next) = cell.go(direction) else {
+                    continue;
+                };
+                let p = next.point();
+                if collected.contains(&p) || *next.value() != *cell.value() {
+                    continue;
+                }
+                region.add_point(Point2Disize::from(p));
+                collected.insert(p);
+                queue.push_back(next);
+            }
+        }
+        regions.push(region);
+    }
+    regions
+}
+
+fn abs<N: Signed>(n: N) -> N {
+    if n < N::ZERO {
+        -n
+    } else {
+        n
+    }
+}
+
+fn two<N: Integer>() -> N {
+    N::ONE + N::ONE
+}
+
+// Shoelace formula, positive when the vertices run clockwise on screen (y pointing down).
+// The polygon functions take signed types, since edges and cross products go negative.
+pub fn twice_signed_area<N: Signed>(vertices: &[Point2D<N>]) -> N {
+    let n = vertices.len();
+    (0..n)
+        .map(|i| {
+            let (a, b) = (vertices[i], vertices[(i + 1) % n]);
+            a.x * b.y - b.x * a.y
+        })
+        .fold(N::ZERO, |sum, x| sum + x)
+}
+
+// Rounds down for polygons with a half unit of area
+pub fn polygon_area<N: Signed>(vertices: &[Point2D<N>]) -> N {
+    abs(twice_signed_area(vertices)) / two()
+}
+
+// Lattice points along the edges, edges don't need to be axis aligned
+pub fn boundary_points<N: Signed>(vertices: &[Point2D<N>]) -> N {
+    let n = vertices.len();
+    (0..n)
+        .map(|i| {
+            let d = vertices[(i + 1) % n] - vertices[i];
+            gcd(d.x, d.y)
+        })
+        .fold(N::ZERO, |sum, x| sum + x)
+}
+
+// Pick's theorem: A = I + B / 2 - 1
+pub fn interior_points<N: Signed>(vertices: &[Point2D<N>]) -> N {
+    (abs(twice_signed_area(vertices)) - boundary_points(vertices) + two()) / two()
+}
+
+// Every lattice point inside or on the polygon, i.e. the cells dug out by walking its edges
+pub fn lattice_points<N: Signed>(vertices: &[Point2D<N>]) -> N {
+    interior_points(vertices) + boundary_points(vertices)
+}
+
+// Vertices visited by following a dig plan from the origin
+pub fn dig_plan_vertices<N, I>(steps: I) -> Vec<Point2D<N>>
+where
+    N: Signed,
+    I: IntoIterator<Item = (Direction, N)>,
+{
+    let mut position = Point2D::new(N::ZERO, N::ZERO);
+    let mut vertices = vec![];
+    for (direction, length) in steps {
+        vertices.push(position);
+        position = match direction {
+            Direction::Up => Point2D::new(position.x, position.y - length),
+            Direction::Down => Point2D::new(position.x, position.y + length),
+            Direction::Left => Point2D::new(position.x - length, position.y),
+            Direction::Right => Point2D::new(position.x + length, position.y),
+        };
+    }
+    vertices
+}
+
+pub fn dig_plan_area<N, I>(steps: I) -> N
+where
+    N: Signed,
+    I: IntoIterator<Item = (Direction, N)>,
+{
+    lattice_points(&dig_plan_vertices(steps))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Containment {
+    Inside,
+    Boundary,
+    Outside,
+}
+
+fn on_segment<N: Signed>(a: Point2D<N>, b: Point2D<N>, p: Point2D<N>) -> bool {
+    let (ab, ap) = (b - a, p - a);
+    ab.x * ap.y == ab.y * ap.x
+        && p.x >= a.x.min(b.x)
+        && p.x <= a.x.max(b.x)
+        && p.y >= a.y.min(b.y)
+        && p.y <= a.y.max(b.y)
+}
+
+// Ray casting towards +x with exact integer comparisons
+pub fn point_in_polygon<N: Signed>(vertices: &[Point2D<N>], point: &Point2D<N>) -> Containment {
+    let p = *point;
+    let n = vertices.len();
+    let mut inside = false;
+
+    for i in 0..n {
+        let (a, b) = (vertices[i], vertices[(i + 1) % n]);
+        if on_segment(a, b, p) {
+            return Containment::Boundary;
+        }
+        if (a.y > p.y) != (b.y > p.y) {
+            // Is p left of where the edge crosses its row
+            let lhs = (p.x - a.x) * (b.y - a.y);
+            let rhs = (p.y - a.y) * (b.x - a.x);
+            if (b.y > a.y && lhs < rhs) || (b.y < a.y && lhs > rhs) {
+                inside = !inside;
+            }
+        }
+    }
+
+    if inside {
+        Containment::Inside
+    } else {
+        Containment::Outside
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_shape() {
+        // An O with a hole in the middle and a stray cell touching a corner
+        let grid: Grid<Vec<char>> = "AAA.\nA.A.\nAAA.\n...A".parse().unwrap();
+        let shape: Shape = grid
+            .iter()
+            .filter(|cell| *cell.value() == 'A')
+            .map(|cell| Point2Disize::from(cell.point()))
+            .collect();
+
+        assert_eq!(shape.area(), 9);
+        assert_eq!(shape.perimeter(), 20);
+        assert_eq!(shape.sides(), 12);
+        assert_eq!(shape.corners(), 12);
+        assert_eq!(
+            shape.bounding_box(),
+            Some((Point2Disize::new(0, 0), Point2Disize::new(3, 3)))
+        );
+
+        let boundaries = shape.boundaries();
+        assert_eq!(boundaries.outer.len(), 2);
+        assert_eq!(boundaries.holes.len(), 1);
+        assert_eq!(
+            boundaries.outer[0],
+            [(0, 0), (3, 0), (3, 3), (0, 3)].map(Point2Disize::from)
+        );
+        assert_eq!(
+            boundaries.holes[0],
+            [(1, 1), (1, 2), (2, 2), (2, 1)].map(Point2Disize::from)
+        );
+    }
+
+    #[test]
+    fn test_regions() {
+        let grid: Grid<Vec<char>> = "AAB\nABB\nCCC".parse().unwrap();
+        let regions = regions(&grid);
+        assert_eq!(regions.len(), 3);
+        assert_eq!(
+            regions.iter().map(|r| r.area()).collect::<Vec<_>>(),
+            [3, 3, 3]
+        );
+        assert_eq!(regions[0].centroid(), Some((1.0 / 3.0, 1.0 / 3.0)));
+    }
+
+    #[test]
+    fn test_dig_plan() {
+        use Direction::*;
+        let plan = [
+            (Right, 6),
+            (Down, 5),
+            (Left, 2),
+            (Down, 2),
+            (Right, 2),
+            (Down, 2),
+            (Left, 5),
+            (Up, 2),
+            (Left, 1),
+            (Up, 2),
+            (Right, 2),
+            (Up, 3),
+            (Left, 2),
+            (Up, 2),
+        ];
+        assert_eq!(dig_plan_area::<i64, _>(plan), 62);
+
+        let huge = [
+            (Right, 10i64.pow(7)),
+            (Down, 10i64.pow(7)),
+            (Left, 10i64.pow(7)),
+            (Up, 10i64.pow(7)),
+        ];
+        assert_eq!(dig_plan_area(huge), (10i64.pow(7) + 1).pow(2));
+
+        let counter_clockwise = [(Down, 3), (Right, 4), (Up, 3), (Left, 4)];
+        assert_eq!(dig_plan_area::<i64, _>(counter_clockwise), 20);
+    }
+
+    #[test]
+    fn test_polygon() {
+        let triangle = [(0, 0), (4, 0), (0, 4)].map(Point2D::<i32>::from);
+        assert_eq!(polygon_area(&triangle), 8);
+        assert_eq!(boundary_points(&triangle), 12);
+        assert_eq!(interior_points(&triangle), 3);
+
+        let inside = |x, y| point_in_polygon(&triangle, &Point2D::new(x, y));
+        assert_eq!(inside(1, 1), Containment::Inside);
+        assert_eq!(inside(2, 2), Containment::Boundary);
+        assert_eq!(inside(0, 3), Containment::Boundary);
+        assert_eq!(inside(3, 3), Containment::Outside);
+        assert_eq!(inside(-1, 0), Containment::Outside);
+
+        let reversed = [(0, 4), (4, 0), (0, 0)].map(Point2D::<i32>::from);
+        assert_eq!(twice_signed_area(&reversed), -16);
+        assert_eq!(polygon_area(&reversed), 8);
+        assert_eq!(interior_points(&reversed), 3);
+        assert_eq!(
+            point_in_polygon(&reversed, &Point2D::new(1, 1)),
+            Containment::Inside
+        );
+    }
+}
@@ -0,0 +1,472 @@
+use std::{
+    cmp::Reverse,
+    collections::{BTreeSet, BinaryHeap, HashMap},
+    hash::Hash,
+    ops::{Add, Index},
+};
+
+use super::grid::{Grid, Point};
+
+pub type NodeId = usize;
+
+// Nodes are interned, so each distinct N gets one NodeId in insertion order
+#[derive(Debug, Clone)]
+pub struct Graph<N, E = ()> {
+    directed: bool,
+    nodes: Vec<N>,
+    ids: HashMap<N, NodeId>,
+    edges: Vec<Vec<(NodeId, E)>>,
+}
+
+impl<N, E> Graph<N, E>
+where
+    N: Clone + Eq + Hash,
+{
+    fn new(directed: bool) -> Self {
+        Self {
+            directed,
+            nodes: vec![],
+            ids: HashMap::new(),
+            edges: vec![],
+        }
+    }
+
+    pub fn directed() -> Self {
+        Self::new(true)
+    }
+
+    pub fn undirected() -> Self {
+        Self::new(false)
+    }
+
+    pub fn is_directed(&self) -> bool {
+        self.directed
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn add_node(&mut self, node: N) -> NodeId {
+        if let Some(&id) = self.ids.get(&node) {
+            return id;
+        }
+        let id = self.nodes.len();
+        self.ids.insert(node.clone(), id);
+        self.nodes.push(node);
+        self.edges.push(vec![]);
+        id
+    }
+
+    pub fn id(&self, node: &N) -> Option<NodeId> {
+        self.ids.get(node).copied()
+    }
+
+    pub fn node(&self, id: NodeId) -> &N {
+        &self.nodes[id]
+    }
+
+    pub fn nodes(&self) -> impl Iterator<Item = (NodeId, &N)> + '_ {
+        self.nodes.iter().enumerate()
+    }
+
+    pub fn add_edge(&mut self, from: N, to: N, edge: E)
+    where
+        E: Clone,
+    {
+        let (from, to) = (self.add_node(from), self.add_node(to));
+        self.add_edge_ids(from, to, edge);
+    }
+
+    pub fn add_edge_ids(&mut self, from: NodeId, to: NodeId, edge: E)
+    where
+        E: Clone,
+    {
+        if !self.directed && from != to {
+            self.edges[to].push((from, edge.clone()));
+        }
+        self.edges[from].push((to, edge));
+    }
+
+    pub fn neighbours(&self, id: NodeId) -> impl Iterator<Item = (NodeId, &E)> + '_ {
+        self.edges[id].iter().map(|(to, edge)| (*to, edge))
+    }
+
+    pub fn degree(&self, id: NodeId) -> usize {
+        self.edges[id].len()
+    }
+
+    pub fn edge(&self, from: &N, to: &N) -> Option<&E> {
+        let (from, to) = (self.id(from)?, self.id(to)?);
+        self.edges[from]
+            .iter()
+            .find(|(id, _)| *id == to)
+            .map(|(_, edge)| edge)
+    }
+
+    pub fn has_edge(&self, from: &N, to: &N) -> bool {
+        self.edge(from, to).is_some()
+    }
+
+    // Each undirected edge is only reported once, from its lower id
+    pub fn edges(&self) -> impl Iterator<Item = (NodeId, NodeId, &E)> + '_ {
+        self.edges.iter().enumerate().flat_map(move |(from, out)| {
+            out.iter()
+                .filter(move |(to, _)| self.directed || from <= *to)
+                .map(move |(to, edge)| (from, *to, edge))
+        })
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edges().count()
+    }
+
+    // Only the nodes matching keep and the edges between them, ids are reassigned
+    pub fn subgraph(&self, keep: impl Fn(&N) -> bool) -> Self
+    where
+        E: Clone,
+    {
+        let mut out = Self::new(self.directed);
+        for (_, node) in self.nodes().filter(|(_, node)| keep(node)) {
+            out.add_node(node.clone());
+        }
+        for (from, to, edge) in self.edges() {
+            if let (Some(a), Some(b)) = (out.id(&self.nodes[from]), out.id(&self.nodes[to])) {
+                out.add_edge_ids(a, b, edge.clone());
+            }
+        }
+        out
+    }
+
+    // Kahn's algorithm, taking the lowest ready id first. On failure returns the nodes
+    // of one cycle, in edge order.
+    pub fn toposort(&self) -> Result<Vec<NodeId>, Vec<NodeId>> {
+        assert!(self.directed, "Only directed graphs can be sorted");
+        let mut in_degree = vec![0; self.len()];
+        for (_, to, _) in self.edges() {
+            in_degree[to] += 1;
+        }
+
+        let mut ready: BinaryHeap<Reverse<NodeId>> = (0..self.len())
+            .filter(|&id| in_degree[id] == 0)
+            .map(Reverse)
+            .collect();
+        let mut order = vec![];
+        while let Some(Reverse(id)) = ready.pop() {
+            order.push(id);
+            for (to, _) in self.neighbours(id) {
+                in_degree[to] -= 1;
+                if in_degree[to] == 0 {
+                    ready.push(Reverse(to));
+                }
+            }
+        }
+
+        if order.len() == self.len() {
+            return Ok(order);
+        }
+
+        // Everything left has an incoming edge from something else left, so walking
+        // backwards along those edges must eventually repeat
+        let remaining = |id: NodeId| in_degree[id] > 0;
+        let mut predecessor = vec![None; self.len()];
+        for (from, to, _) in self.edges() {
+            if remaining(from) && remaining(to) {
+                predecessor[to] = Some(from);
+            }
+        }
+        let mut seen = vec![false; self.len()];
+        let mut id = (0..self.len()).find(|&id| remaining(id)).unwrap();
+        while !seen[id] {
+            seen[id] = true;
+            id = predecessor[id].unwrap();
+        }
+        let mut cycle = vec![id];
+        let mut next = predecessor[id].unwrap();
+        while next != id {
+            cycle.push(next);
+            next = predecessor[next].unwrap();
+        }
+        cycle.reverse();
+        Err(cycle)
+    }
+
+    // Tarjan's algorithm without recursion, components come out in reverse topological order
+    pub fn strongly_connected_components(&self) -> Vec<Vec<NodeId>> {
+        let n = self.len();
+        let mut index = vec![usize::MAX; n];
+        let mut low = vec![0; n];
+        let mut on_stack = vec![false; n];
+        let mut stack = vec![];
+        let mut components = vec![];
+        let mut next_index = 0;
+
+        for root in 0..n {
+            if index[root] != usize::MAX {
+                continue;
+            }
+            // (node, position in its adjacency list)
+            let mut work = vec![(root, 0)];
+            while let Some(&mut (id, ref mut edge)) = work.last_mut() {
+                if *edge == 0 && index[id] == usize::MAX {
+                    index[id] = next_index;
+                    low[id] = next_index;
+                    next_index += 1;
+                    stack.push(id);
+                    on_stack[id] = true;
+                }
+
+                if let Some(&(to, _)) = self.edges[id].get(*edge) {
+                    *edge += 1;
+                    if index[to] == usize::MAX {
+                        work.push((to, 0));
+                    } else if on_stack[to] {
+                        low[id] = low[id].min(index[to]);
+                    }
+                    continue;
+                }
+
+                work.pop();
+                if let Some(&(parent, _)) = work.last() {
+                    low[parent] = low[parent].min(low[id]);
+                }
+                if low[id] == index[id] {
+                    let mut component = vec![];
+                    loop {
+                        let member = stack.pop().unwrap();
+                        on_stack[member] = false;
+                        component.push(member);
+                        if member == id {
+                            break;
+                        }
+                    }
+                    components.push(component);
+                }
+            }
+        }
+
+        components
+    }
+
+    // Bron-Kerbosch with pivoting, edge direction is ignored
+    pub fn maximal_cliques(&self) -> Vec<Vec<NodeId>> {
+        let mut adjacent = vec![BTreeSet::new(); self.len()];
+        for (from, to, _) in self.edges() {
+            if from != to {
+                adjacent[from].insert(to);
+                adjacent[to].insert(from);
+            }
+        }
+
+        fn bron_kerbosch(
+            adjacent: &[BTreeSet<NodeId>],
+            clique: &mut Vec<NodeId>,
+            mut candidates: BTreeSet<NodeId>,
+            mut excluded: BTreeSet<NodeId>,
+            out: &mut Vec<Vec<NodeId>>,
+        ) {
+            let Some(&pivot) = candidates
+                .union(&excluded)
+                .max_by_key(|&&id| adjacent[id].len())
+            else {
+                out.push(clique.clone());
+                return;
+            };
+
+            let options: Vec<NodeId> = candidates.difference(&adjacent[pivot]).copied().collect();
+            for id in options {
+                clique.push(id);
+                bron_kerbosch(
+                    adjacent,
+                    clique,
+                    candidates.intersection(&adjacent[id]).copied().collect(),
+                    excluded.intersection(&adjacent[id]).copied().collect(),
+                    out,
+                );
+                clique.pop();
+                candidates.remove(&id);
+                excluded.insert(id);
+            }
+        }
+
+        let mut out = vec![];
+        bron_kerbosch(
+            &adjacent,
+            &mut vec![],
+            (0..self.len()).collect(),
+            BTreeSet::new(),
+            &mut out,
+        );
+        for clique in &mut out {
+            clique.sort_unstable();
+        }
+        out
+    }
+
+    pub fn maximum_clique(&self) -> Vec<NodeId> {
+        self.maximal_cliques()
+            .into_iter()
+            .max_by_key(Vec::len)
+            .unwrap_or_default()
+    }
+}
+
+impl<N, E> Graph<N, E>
+where
+    N: Clone + Eq + Hash,
+    E: Copy + Ord + Add<Output = E>,
+{
+    // Collapses chains of degree 2 nodes into single weighted edges, leaving only
+    // junctions, dead ends and anything keep asks for. Undirected graphs only, and
+    // loops with no junction on them disappear entirely.
+    pub fn contract_corridors(&self, keep: impl Fn(&N) -> bool) -> Self {
+        assert!(!self.directed, "Corridors only make sense undirected");
+        let is_junction = |id: NodeId| self.degree(id) != 2 || keep(&self.nodes[id]);
+
+        let mut out = Self::undirected();
+        for id in (0..self.len()).filter(|&id| is_junction(id)) {
+            out.add_node(self.nodes[id].clone());
+        }
+
+        for start in (0..self.len()).filter(|&id| is_junction(id)) {
+            for &(first, weight) in &self.edges[start] {
+                let (mut previous, mut current, mut total) = (start, first, weight);
+                while !is_junction(current) {
+                    let &(next, weight) = self.edges[current]
+                        .iter()
+                        .find(|(to, _)| *to != previous)
+                        .unwrap_or(&self.edges[current][0]);
+                    (previous, current, total) = (current, next, total + weight);
+                }
+
+                // Every corridor is walked from both ends, so only add it from the lower id
+                let (from, to) = (out.ids[&self.nodes[start]], out.ids[&self.nodes[current]]);
+                if from > to {
+                    continue;
+                }
+                match out.edges[from].iter().position(|(id, _)| *id == to) {
+                    Some(i) if out.edges[from][i].1 <= total => {}
+                    Some(i) => {
+                        out.edges[from][i].1 = total;
+                        if let Some(back) = out.edges[to].iter_mut().find(|(id, _)| *id == from) {
+                            back.1 = total;
+                        }
+                    }
+                    None => out.add_edge_ids(from, to, total),
+                }
+            }
+        }
+
+        out
+    }
+}
+
+// Passable cells joined to their passable neighbours by edges of length 1
+pub fn from_grid<T, F>(grid: &Grid<T>, passable: F) -> Graph<Point, usize>
+where
+    T: Index<usize>,
+    F: Fn(&T::Output) -> bool,
+{
+    let mut graph = Graph::undirected();
+    let is_passable = |point: &Point| {
+        grid.cell_at_point(point)
+            .is_some_and(|cell| passable(&cell.value()))
+    };
+
+    for point in grid.points().filter(is_passable) {
+        graph.add_node(point);
+        // Only look right and down so each edge is added once
+        for next in [point.right(), point.down()] {
+            if is_passable(&next) {
+                graph.add_edge(point, next, 1);
+            }
+        }
+    }
+
+    graph
+}
+
+#[cfg(test)]
+mod test {
+    use itertools::Itertools;
+
+    use super::*;
+
+    #[test]
+    fn test_toposort() {
+        let mut graph: Graph<char> = Graph::directed();
+        for (a, b) in [('a', 'b'), ('b', 'c'), ('a', 'c'), ('d', 'c')] {
+            graph.add_edge(a, b, ());
+        }
+        let order: String = graph
+            .toposort()
+            .unwrap()
+            .into_iter()
+            .map(|id| *graph.node(id))
+            .collect();
+        assert_eq!(order, "abdc");
+
+        graph.add_edge('c', 'e', ());
+        graph.add_edge('e', 'b', ());
+        let cycle: String = graph
+            .toposort()
+            .unwrap_err()
+            .into_iter()
+            .map(|id| *graph.node(id))
+            .collect();
+        assert_eq!(cycle.len(), 3);
+        assert!("bcebce".contains(&cycle));
+    }
+
+    #[test]
+    fn test_scc() {
+        let mut graph: Graph<u32> = Graph::directed();
+        for (a, b) in [(1, 2), (2, 3), (3, 1), (3, 4), (4, 5), (5, 4), (6, 6)] {
+            graph.add_edge(a, b, ());
+        }
+        let mut components: Vec<Vec<u32>> = graph
+            .strongly_connected_components()
+            .into_iter()
+            .map(|c| c.into_iter().map(|id| *graph.node(id)).sorted().collect())
+            .collect();
+        components.sort();
+        assert_eq!(components, vec![vec![1, 2, 3], vec![4, 5], vec![6]]);
+    }
+
+    #[test]
+    fn test_cliques() {
+        let mut graph: Graph<&str> = Graph::undirected();
+        for (a, b) in [("a", "b"), ("b", "c"), ("a", "c"), ("c", "d"), ("d", "e")] {
+            graph.add_edge(a, b, ());
+        }
+        assert_eq!(graph.maximal_cliques().len(), 3);
+        let clique: Vec<&str> = graph
+            .maximum_clique()
+            .into_iter()
+            .map(|id| *graph.node(id))
+            .collect();
+        assert_eq!(clique, vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_grid() {
+        let grid: Grid<Vec<char>> = "#.###\n#...#\n#.#.#\n#...#\n###.#".parse().unwrap();
+        let graph = from_grid(&grid, |&c| c == '.');
+        assert_eq!(graph.len(), 10);
+        assert_eq!(graph.edge_count(), 10);
+
+        let contracted = graph.contract_corridors(|_| false);
+        let (top, bottom) = (Point::new(1, 0), Point::new(3, 4));
+        assert_eq!(contracted.len(), 4);
+        assert_eq!(contracted.edge(&Point::new(1, 1), &top), Some(&1));
+        assert_eq!(contracted.edge(&Point::new(3, 3), &bottom), Some(&1));
+        assert_eq!(
+            contracted.edge(&Point::new(1, 1), &Point::new(3, 3)),
+            Some(&4)
+        );
+    }
+}
@@ -1,3 +1,7 @@
+pub mod bitgrid;
+pub mod nd;
+pub mod renderer;
+
 use std::{
     cell::{Ref, RefCell, RefMut},
     fmt::{Display, Write},
@@ -5,17 +9,14 @@ use std::{
     str::FromStr,
 };
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub struct Point {
-    pub x: usize,
-    pub y: usize,
-}
+use nd::Coordinate;
 
-impl Point {
-    pub const fn new(x: usize, y: usize) -> Self {
-        Point { x, y }
-    }
+use crate::point2d::{Point2D, Point2Disize};
+
+// Grids are indexed by the same point type used for geometry, just unsigned
+pub type Point = Point2D<usize>;
 
+impl Point {
     pub fn on<'a, T: Index<usize>>(&self, grid: &'a Grid<T>) -> Option<GridCell<'a, T>> {
         grid.cell_at_point(self)
     }
@@ -53,10 +54,6 @@ impl Point {
         }
     }
 
-    pub fn manhattan_distance(&self, other: &Point) -> usize {
-        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
-    }
-
     pub fn tuple(&self) -> (usize, usize) {
         (self.x, self.y)
     }
@@ -80,12 +77,8 @@ impl std::ops::Add<Direction> for Point {
     type Output = Option<Point>;
 
     fn add(self, rhs: Direction) -> Self::Output {
-        self + match rhs {
-            Direction::Up => (0, -1),
-            Direction::Down => (0, 1),
-            Direction::Left => (-1, 0),
-            Direction::Right => (1, 0),
-        }
+        let delta = Point2Disize::from(rhs);
+        self + (delta.x, delta.y)
     }
 }
 
@@ -105,21 +98,6 @@ impl std::ops::Sub<Direction> for Point {
     }
 }
 
-impl Display for Point {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "({}, {})", self.x, self.y)
-    }
-}
-
-impl<T> From<(T, T)> for Point
-where
-    T: Into<usize>,
-{
-    fn from((x, y): (T, T)) -> Self {
-        Point::new(x.into(), y.into())
-    }
-}
-
 pub struct Grid<T: Index<usize>> {
     width: usize,
     height: usize,
@@ -159,6 +137,30 @@ impl<T: Index<usize>> Grid<T> {
         self.in_bounds(point.x, point.y)
     }
 
+    // The grid point for a point of any integer type, if it lands inside the grid
+    pub fn checked_point<N>(&self, point: Point2D<N>) -> Option<Point>
+    where
+        N: Copy + TryInto<usize>,
+    {
+        point.to_point().filter(|point| self.in_bounds_point(point))
+    }
+
+    pub fn dims(&self) -> [usize; 2] {
+        [self.width, self.height]
+    }
+
+    pub fn points(&self) -> impl Iterator<Item = Point> + '_ {
+        (0..self.len()).map(|i| Point::from_linear_index(i, &self.dims()))
+    }
+
+    pub fn neighbours(&self, point: &Point) -> Vec<Point> {
+        point.face_neighbours_within(&self.dims())
+    }
+
+    pub fn all_neighbours(&self, point: &Point) -> Vec<Point> {
+        point.all_neighbours_within(&self.dims())
+    }
+
     // Used for indexing as well
     pub fn cell_at(&self, x: usize, y: usize) -> Option<GridCell<'_, T>> {
         self.in_bounds(x, y)
@@ -178,6 +180,10 @@ impl<T: Index<usize>> Grid<T> {
         GridIter::new(self)
     }
 
+    pub fn render(&self) -> renderer::GridRenderer<'_, T> {
+        renderer::GridRenderer::new(self)
+    }
+
     pub fn find_by_value<F>(&self, pred: F) -> Option<GridCell<'_, T>>
     where
         F: Fn(T::Output) -> bool,
@@ -403,6 +409,16 @@ impl Direction {
         &[Self::Up, Self::Down, Self::Left, Self::Right]
     }
 
+    // Position within Direction::all()
+    pub const fn index(&self) -> usize {
+        match self {
+            Self::Up => 0,
+            Self::Down => 1,
+            Self::Left => 2,
+            Self::Right => 3,
+        }
+    }
+
     pub fn from<'a, T: Index<usize>>(&self, cell: &GridCell<'a, T>) -> Option<GridCell<'a, T>> {
         cell.go(self)
     }
@@ -475,3 +491,47 @@ impl Display for Direction {
 }
 
 pub type Step = [Direction];
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn sorted(points: Vec<Point>) -> Vec<(usize, usize)> {
+        let mut out: Vec<_> = points.iter().map(|p| (p.x, p.y)).collect();
+        out.sort();
+        out
+    }
+
+    #[test]
+    fn test_points() {
+        let grid = Grid::fill(3, 2, '.');
+        assert_eq!(grid.dims(), [3, 2]);
+        assert_eq!(
+            grid.points().map(|p| (p.x, p.y)).collect::<Vec<_>>(),
+            [(0, 0), (1, 0), (2, 0), (0, 1), (1, 1), (2, 1)]
+        );
+        assert_eq!(Grid::fill(0, 4, '.').points().count(), 0);
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = Grid::fill(3, 2, '.');
+        let neighbours = |x, y| sorted(grid.neighbours(&Point::new(x, y)));
+        let all_neighbours = |x, y| sorted(grid.all_neighbours(&Point::new(x, y)));
+
+        assert_eq!(neighbours(0, 0), [(0, 1), (1, 0)]);
+        assert_eq!(neighbours(2, 1), [(1, 1), (2, 0)]);
+        assert_eq!(neighbours(1, 0), [(0, 0), (1, 1), (2, 0)]);
+
+        assert_eq!(all_neighbours(0, 0), [(0, 1), (1, 0), (1, 1)]);
+        assert_eq!(all_neighbours(2, 0), [(1, 0), (1, 1), (2, 1)]);
+        assert_eq!(
+            all_neighbours(1, 1),
+            [(0, 0), (0, 1), (1, 0), (2, 0), (2, 1)]
+        );
+
+        let single = Grid::fill(1, 1, '.');
+        assert!(single.neighbours(&Point::new(0, 0)).is_empty());
+        assert!(single.all_neighbours(&Point::new(0, 0)).is_empty());
+    }
+}
@@ -0,0 +1,347 @@
+use std::{collections::HashSet, ops::Index};
+
+use itertools::Itertools;
+
+use super::{Direction, Grid, Point};
+
+const WORD: usize = u64::BITS as usize;
+
+// One bit per cell per layer, layers let a cell carry e.g. one bit for each direction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitGrid {
+    width: usize,
+    height: usize,
+    layers: usize,
+    words: Vec<u64>,
+}
+
+impl BitGrid {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self::with_layers(width, height, 1)
+    }
+
+    pub fn with_layers(width: usize, height: usize, layers: usize) -> Self {
+        assert!(layers > 0, "BitGrid needs at least one layer");
+        Self {
+            width,
+            height,
+            layers,
+            words: vec![0; (width * height * layers).div_ceil(WORD)],
+        }
+    }
+
+    pub fn directional(width: usize, height: usize) -> Self {
+        Self::with_layers(width, height, Direction::all().len())
+    }
+
+    pub fn for_grid<T: Index<usize>>(grid: &Grid<T>) -> Self {
+        Self::new(grid.width(), grid.height())
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn layers(&self) -> usize {
+        self.layers
+    }
+
+    pub fn in_bounds(&self, point: &Point) -> bool {
+        point.x < self.width && point.y < self.height
+    }
+
+    fn bit(&self, point: &Point, layer: usize) -> (usize, u64) {
+        assert!(
+            self.in_bounds(point) && layer < self.layers,
+            "{point} layer {layer} is outside of a {}x{}x{} BitGrid",
+            self.width,
+            self.height,
+            self.layers
+        );
+        let i = (point.y * self.width + point.x) * self.layers + layer;
+        (i / WORD, 1 << (i % WORD))
+    }
+
+    // Returns true if the bit wasn't already set, like HashSet::insert
+    pub fn set_layer(&mut self, point: &Point, layer: usize) -> bool {
+        let (word, mask) = self.bit(point, layer);
+        let was_set = self.words[word] & mask != 0;
+        self.words[word] |= mask;
+        !was_set
+    }
+
+    pub fn test_layer(&self, point: &Point, layer: usize) -> bool {
+        let (word, mask) = self.bit(point, layer);
+        self.words[word] & mask != 0
+    }
+
+    pub fn clear_layer(&mut self, point: &Point, layer: usize) -> bool {
+        let (word, mask) = self.bit(point, layer);
+        let was_set = self.words[word] & mask != 0;
+        self.words[word] &= !mask;
+        was_set
+    }
+
+    pub fn set(&mut self, point: &Point) -> bool {
+        self.set_layer(point, 0)
+    }
+
+    pub fn test(&self, point: &Point) -> bool {
+        self.test_layer(point, 0)
+    }
+
+    pub fn clear(&mut self, point: &Point) -> bool {
+        self.clear_layer(point, 0)
+    }
+
+    pub fn set_direction(&mut self, point: &Point, direction: &Direction) -> bool {
+        self.set_layer(point, direction.index())
+    }
+
+    pub fn test_direction(&self, point: &Point, direction: &Direction) -> bool {
+        self.test_layer(point, direction.index())
+    }
+
+    pub fn clear_direction(&mut self, point: &Point, direction: &Direction) -> bool {
+        self.clear_layer(point, direction.index())
+    }
+
+    // Is any layer set at this point
+    pub fn test_any(&self, point: &Point) -> bool {
+        (0..self.layers).any(|layer| self.test_layer(point, layer))
+    }
+
+    pub fn reset(&mut self) {
+        self.words.fill(0);
+    }
+
+    pub fn count(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn count_points(&self) -> usize {
+        if self.layers == 1 {
+            self.count()
+        } else {
+            self.points().count()
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&w| w == 0)
+    }
+
+    fn assert_same_shape(&self, other: &Self) {
+        assert!(
+            self.width == other.width && self.height == other.height && self.layers == other.layers,
+            "BitGrids must be the same shape"
+        );
+    }
+
+    pub fn union_with(&mut self, other: &Self) {
+        self.assert_same_shape(other);
+        for (a, b) in self.words.iter_mut().zip(&other.words) {
+            *a |= b;
+        }
+    }
+
+    pub fn intersect_with(&mut self, other: &Self) {
+        self.assert_same_shape(other);
+        for (a, b) in self.words.iter_mut().zip(&other.words) {
+            *a &= b;
+        }
+    }
+
+    pub fn difference_with(&mut self, other: &Self) {
+        self.assert_same_shape(other);
+        for (a, b) in self.words.iter_mut().zip(&other.words) {
+            *a &= !b;
+        }
+    }
+
+    // Every set bit as (point, layer), skipping empty words
+    pub fn iter(&self) -> impl Iterator<Item = (Point, usize)> + '_ {
+        self.words.iter().enumerate().flat_map(move |(w, &word)| {
+            let mut word = word;
+            std::iter::from_fn(move || {
+                (word != 0).then(|| {
+                    let bit = word.trailing_zeros() as usize;
+                    word &= word - 1;
+                    let i = w * WORD + bit;
+                    let cell = i / self.layers;
+                    (
+                        Point::new(cell % self.width, cell / self.width),
+                        i % self.layers,
+                    )
+                })
+            })
+        })
+    }
+
+    // Points with at least one layer set, each yielded once
+    pub fn points(&self) -> impl Iterator<Item = Point> + '_ {
+        self.iter().map(|(point, _)| point).dedup()
+    }
+}
+
+// A set of points that is a BitGrid inside known bounds and a HashSet outside them
+#[derive(Debug, Clone, Default)]
+pub struct PointSet {
+    bits: Option<BitGrid>,
+    overflow: HashSet<Point>,
+    len: usize,
+}
+
+impl PointSet {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn bounded(width: usize, height: usize) -> Self {
+        Self {
+            bits: Some(BitGrid::new(width, height)),
+            ..Default::default()
+        }
+    }
+
+    pub fn for_grid<T: Index<usize>>(grid: &Grid<T>) -> Self {
+        Self::bounded(grid.width(), grid.height())
+    }
+
+    pub fn insert(&mut self, point: Point) -> bool {
+        let inserted = match &mut self.bits {
+            Some(bits) if bits.in_bounds(&point) => bits.set(&point),
+            _ => self.overflow.insert(point),
+        };
+        if inserted {
+            self.len += 1;
+        }
+        inserted
+    }
+
+    pub fn contains(&self, point: &Point) -> bool {
+        match &self.bits {
+            Some(bits) if bits.in_bounds(point) => bits.test(point),
+            _ => self.overflow.contains(point),
+        }
+    }
+
+    pub fn remove(&mut self, point: &Point) -> bool {
+        let removed = match &mut self.bits {
+            Some(bits) if bits.in_bounds(point) => bits.clear(point),
+            _ => self.overflow.remove(point),
+        };
+        if removed {
+            self.len -= 1;
+        }
+        removed
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn clear(&mut self) {
+        if let Some(bits) = &mut self.bits {
+            bits.reset();
+        }
+        self.overflow.clear();
+        self.len = 0;
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Point> + '_ {
+        self.bits
+            .iter()
+            .flat_map(|bits| bits.points())
+            .chain(self.overflow.iter().copied())
+    }
+}
+
+impl From<BitGrid> for PointSet {
+    fn from(bits: BitGrid) -> Self {
+        let bits = if bits.layers == 1 {
+            bits
+        } else {
+            let mut flat = BitGrid::new(bits.width, bits.height);
+            for point in bits.points() {
+                flat.set(&point);
+            }
+            flat
+        };
+        Self {
+            len: bits.count(),
+            bits: Some(bits),
+            overflow: HashSet::new(),
+        }
+    }
+}
+
+impl Extend<Point> for PointSet {
+    fn extend<I: IntoIterator<Item = Point>>(&mut self, iter: I) {
+        for point in iter {
+            self.insert(point);
+        }
+    }
+}
+
+impl FromIterator<Point> for PointSet {
+    fn from_iter<I: IntoIterator<Item = Point>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_bitgrid() {
+        let mut a = BitGrid::directional(10, 10);
+        let p = Point::new(9, 3);
+
+        assert!(a.set_direction(&p, &Direction::Left));
+        assert!(!a.set_direction(&p, &Direction::Left));
+        assert!(a.set_direction(&p, &Direction::Up));
+        assert!(a.test_direction(&p, &Direction::Up));
+        assert!(!a.test_direction(&p, &Direction::Down));
+        assert!(a.set_direction(&Point::new(0, 0), &Direction::Down));
+
+        assert_eq!(a.count(), 3);
+        assert_eq!(a.count_points(), 2);
+        assert_eq!(a.points().collect::<Vec<_>>(), [Point::new(0, 0), p]);
+
+        let mut b = BitGrid::directional(10, 10);
+        b.set_direction(&p, &Direction::Up);
+        b.set_direction(&Point::new(5, 5), &Direction::Right);
+
+        let mut union = a.clone();
+        union.union_with(&b);
+        assert_eq!(union.count(), 4);
+
+        a.intersect_with(&b);
+        assert_eq!(a.iter().collect::<Vec<_>>(), [(p, Direction::Up.index())]);
+    }
+
+    #[test]
+    fn test_point_set() {
+        let mut set = PointSet::bounded(4, 4);
+        assert!(set.insert(Point::new(1, 1)));
+        assert!(set.insert(Point::new(10, 1)));
+        assert!(!set.insert(Point::new(1, 1)));
+        assert_eq!(set.len(), 2);
+        assert!(set.contains(&Point::new(10, 1)));
+        assert!(set.remove(&Point::new(1, 1)));
+        assert!(!set.contains(&Point::new(1, 1)));
+        assert_eq!(set.iter().collect::<Vec<_>>(), [Point::new(10, 1)]);
+    }
+}
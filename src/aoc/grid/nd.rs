@@ -0,0 +1,306 @@
+use std::{
+    fmt::Display,
+    hash::Hash,
+    ops::{Index, IndexMut},
+};
+
+use super::Point;
+
+// Anything that can address a cell in a dense, row-major grid of any dimension
+pub trait Coordinate: Copy + Eq + Hash + Sized {
+    const DIMENSIONS: usize;
+
+    fn origin() -> Self;
+    fn axis(&self, axis: usize) -> usize;
+    fn with_axis(&self, axis: usize, value: usize) -> Self;
+
+    fn in_bounds(&self, dims: &[usize]) -> bool {
+        (0..Self::DIMENSIONS).all(|axis| self.axis(axis) < dims[axis])
+    }
+
+    // The first axis varies fastest, so for 2D this is y * width + x
+    fn linear_index(&self, dims: &[usize]) -> Option<usize> {
+        self.in_bounds(dims).then(|| {
+            (0..Self::DIMENSIONS)
+                .rev()
+                .fold(0, |index, axis| index * dims[axis] + self.axis(axis))
+        })
+    }
+
+    fn from_linear_index(mut index: usize, dims: &[usize]) -> Self {
+        let mut out = Self::origin();
+        for (axis, &dim) in dims.iter().enumerate().take(Self::DIMENSIONS) {
+            out = out.with_axis(axis, index % dim);
+            index /= dim;
+        }
+        out
+    }
+
+    fn offset(&self, axis: usize, delta: isize) -> Option<Self> {
+        self.axis(axis)
+            .checked_add_signed(delta)
+            .map(|value| self.with_axis(axis, value))
+    }
+
+    fn offset_by(&self, deltas: &[isize]) -> Option<Self> {
+        deltas
+            .iter()
+            .enumerate()
+            .try_fold(*self, |point, (axis, &delta)| point.offset(axis, delta))
+    }
+
+    // Neighbours sharing a face, 2 * D of them away from any edge
+    fn face_neighbours(&self) -> Vec<Self> {
+        (0..Self::DIMENSIONS)
+            .flat_map(|axis| [self.offset(axis, -1), self.offset(axis, 1)])
+            .flatten()
+            .collect()
+    }
+
+    // Neighbours sharing a face, edge or corner, 3^D - 1 of them away from any edge
+    fn all_neighbours(&self) -> Vec<Self> {
+        let count = 3usize.pow(Self::DIMENSIONS as u32);
+        let centre = count / 2;
+        (0..count)
+            .filter(|&i| i != centre)
+            .filter_map(|i| {
+                let deltas: Vec<isize> = (0..Self::DIMENSIONS)
+                    .map(|axis| (i / 3usize.pow(axis as u32) % 3) as isize - 1)
+                    .collect();
+                self.offset_by(&deltas)
+            })
+            .collect()
+    }
+
+    fn face_neighbours_within(&self, dims: &[usize]) -> Vec<Self> {
+        self.face_neighbours()
+            .into_iter()
+            .filter(|p| p.in_bounds(dims))
+            .collect()
+    }
+
+    fn all_neighbours_within(&self, dims: &[usize]) -> Vec<Self> {
+        self.all_neighbours()
+            .into_iter()
+            .filter(|p| p.in_bounds(dims))
+            .collect()
+    }
+
+    fn manhattan_distance(&self, other: &Self) -> usize {
+        (0..Self::DIMENSIONS)
+            .map(|axis| self.axis(axis).abs_diff(other.axis(axis)))
+            .sum()
+    }
+}
+
+impl Coordinate for Point {
+    const DIMENSIONS: usize = 2;
+
+    fn origin() -> Self {
+        Point::new(0, 0)
+    }
+
+    fn axis(&self, axis: usize) -> usize {
+        match axis {
+            0 => self.x,
+            1 => self.y,
+            _ => panic!("Point has no axis {axis}"),
+        }
+    }
+
+    fn with_axis(&self, axis: usize, value: usize) -> Self {
+        match axis {
+            0 => Point::new(value, self.y),
+            1 => Point::new(self.x, value),
+            _ => panic!("Point has no axis {axis}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PointN<const D: usize>(pub [usize; D]);
+
+pub type Point3D = PointN<3>;
+pub type Point4D = PointN<4>;
+
+impl<const D: usize> PointN<D> {
+    pub const fn new(coords: [usize; D]) -> Self {
+        Self(coords)
+    }
+}
+
+impl Point3D {
+    pub const fn xyz(x: usize, y: usize, z: usize) -> Self {
+        Self([x, y, z])
+    }
+}
+
+impl<const D: usize> Coordinate for PointN<D> {
+    const DIMENSIONS: usize = D;
+
+    fn origin() -> Self {
+        Self([0; D])
+    }
+
+    fn axis(&self, axis: usize) -> usize {
+        self.0[axis]
+    }
+
+    fn with_axis(&self, axis: usize, value: usize) -> Self {
+        let mut out = *self;
+        out.0[axis] = value;
+        out
+    }
+}
+
+impl<const D: usize> Index<usize> for PointN<D> {
+    type Output = usize;
+
+    fn index(&self, axis: usize) -> &Self::Output {
+        &self.0[axis]
+    }
+}
+
+impl<const D: usize> Display for PointN<D> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "(")?;
+        for (i, n) in self.0.iter().enumerate() {
+            if i != 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{n}")?;
+        }
+        write!(f, ")")
+    }
+}
+
+impl From<Point> for PointN<2> {
+    fn from(point: Point) -> Self {
+        Self([point.x, point.y])
+    }
+}
+
+impl From<PointN<2>> for Point {
+    fn from(PointN([x, y]): PointN<2>) -> Self {
+        Point::new(x, y)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GridN<T, const D: usize> {
+    dims: [usize; D],
+    items: Vec<T>,
+}
+
+pub type Grid3D<T> = GridN<T, 3>;
+
+impl<T, const D: usize> GridN<T, D> {
+    pub fn new(dims: [usize; D], items: Vec<T>) -> Self {
+        assert_eq!(
+            dims.iter().product::<usize>(),
+            items.len(),
+            "GridN items don't match its dimensions"
+        );
+        Self { dims, items }
+    }
+
+    pub fn dims(&self) -> [usize; D] {
+        self.dims
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn in_bounds(&self, point: &PointN<D>) -> bool {
+        point.in_bounds(&self.dims)
+    }
+
+    pub fn get(&self, point: &PointN<D>) -> Option<&T> {
+        point.linear_index(&self.dims).map(|i| &self.items[i])
+    }
+
+    pub fn get_mut(&mut self, point: &PointN<D>) -> Option<&mut T> {
+        point.linear_index(&self.dims).map(|i| &mut self.items[i])
+    }
+
+    pub fn points(&self) -> impl Iterator<Item = PointN<D>> + '_ {
+        (0..self.len()).map(|i| PointN::from_linear_index(i, &self.dims))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (PointN<D>, &T)> + '_ {
+        self.points().zip(self.items.iter())
+    }
+
+    pub fn face_neighbours(&self, point: &PointN<D>) -> Vec<PointN<D>> {
+        point.face_neighbours_within(&self.dims)
+    }
+
+    pub fn all_neighbours(&self, point: &PointN<D>) -> Vec<PointN<D>> {
+        point.all_neighbours_within(&self.dims)
+    }
+}
+
+impl<T: Clone, const D: usize> GridN<T, D> {
+    pub fn fill(dims: [usize; D], val: T) -> Self {
+        Self::new(dims, vec![val; dims.iter().product()])
+    }
+}
+
+impl<T: Default, const D: usize> GridN<T, D> {
+    pub fn default(dims: [usize; D]) -> Self {
+        Self::new(
+            dims,
+            std::iter::repeat_with(Default::default)
+                .take(dims.iter().product())
+                .collect(),
+        )
+    }
+}
+
+impl<T, const D: usize> Index<PointN<D>> for GridN<T, D> {
+    type Output = T;
+
+    fn index(&self, point: PointN<D>) -> &Self::Output {
+        self.get(&point)
+            .unwrap_or_else(|| panic!("{point} is outside of the grid"))
+    }
+}
+
+impl<T, const D: usize> IndexMut<PointN<D>> for GridN<T, D> {
+    fn index_mut(&mut self, point: PointN<D>) -> &mut Self::Output {
+        self.get_mut(&point)
+            .unwrap_or_else(|| panic!("{point} is outside of the grid"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_neighbours() {
+        let p = Point3D::xyz(1, 1, 1);
+        assert_eq!(p.face_neighbours().len(), 6);
+        assert_eq!(p.all_neighbours().len(), 26);
+        assert_eq!(PointN([0; 4]).all_neighbours().len(), 15);
+        assert_eq!(Point::new(0, 0).face_neighbours_within(&[1, 5]).len(), 1);
+    }
+
+    #[test]
+    fn test_grid() {
+        let mut grid = Grid3D::fill([2, 3, 4], 0u8);
+        let p = Point3D::xyz(1, 2, 3);
+        grid[p] = 7;
+
+        assert_eq!(p.linear_index(&grid.dims()), Some(23));
+        assert_eq!(Point3D::from_linear_index(23, &grid.dims()), p);
+        assert_eq!(grid.iter().filter(|(_, &v)| v == 7).count(), 1);
+        assert_eq!(grid.all_neighbours(&p).len(), 7);
+        assert_eq!(grid.get(&Point3D::xyz(2, 0, 0)), None);
+    }
+}
@@ -0,0 +1,334 @@
+use std::{
+    borrow::Borrow,
+    collections::HashMap,
+    fmt::{Display, Write},
+    ops::Index,
+};
+
+use colored::{Color, Colorize};
+
+use super::{Direction, Grid, Point};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Style {
+    pub fg: Option<Color>,
+    pub bg: Option<Color>,
+    pub bold: bool,
+}
+
+impl Style {
+    pub const fn new() -> Self {
+        Self {
+            fg: None,
+            bg: None,
+            bold: false,
+        }
+    }
+
+    pub const fn fg(color: Color) -> Self {
+        Self {
+            fg: Some(color),
+            bg: None,
+            bold: false,
+        }
+    }
+
+    pub const fn bg(color: Color) -> Self {
+        Self {
+            fg: None,
+            bg: Some(color),
+            bold: false,
+        }
+    }
+
+    pub const fn on(self, color: Color) -> Self {
+        Self {
+            bg: Some(color),
+            ..self
+        }
+    }
+
+    pub const fn bold(self) -> Self {
+        Self { bold: true, ..self }
+    }
+
+    pub fn is_plain(&self) -> bool {
+        self.fg.is_none() && self.bg.is_none() && !self.bold
+    }
+
+    // Anything set on `other` wins
+    pub fn merge(self, other: Style) -> Self {
+        Self {
+            fg: other.fg.or(self.fg),
+            bg: other.bg.or(self.bg),
+            bold: self.bold || other.bold,
+        }
+    }
+
+    pub fn paint(&self, s: &str) -> String {
+        if self.is_plain() {
+            return s.to_owned();
+        }
+        let mut painted = s.normal();
+        if let Some(fg) = self.fg {
+            painted = painted.color(fg);
+        }
+        if let Some(bg) = self.bg {
+            painted = painted.on_color(bg);
+        }
+        if self.bold {
+            painted = painted.bold();
+        }
+        painted.to_string()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Overlay {
+    char: Option<char>,
+    style: Style,
+}
+
+type ValueStyle<'a, V> = Box<dyn Fn(&V) -> Style + 'a>;
+type Highlight<'a, V> = Box<dyn Fn(Point, &V) -> Option<Style> + 'a>;
+
+pub struct GridRenderer<'a, T: Index<usize>> {
+    grid: &'a Grid<T>,
+    value_style: Option<ValueStyle<'a, T::Output>>,
+    highlights: Vec<Highlight<'a, T::Output>>,
+    overlays: HashMap<Point, Overlay>,
+    rulers: bool,
+    viewport: Option<(Point, usize, usize)>,
+    colour: bool,
+}
+
+impl<'a, T: Index<usize>> GridRenderer<'a, T> {
+    pub fn new(grid: &'a Grid<T>) -> Self {
+        Self {
+            grid,
+            value_style: None,
+            highlights: vec![],
+            overlays: HashMap::new(),
+            rulers: false,
+            viewport: None,
+            colour: true,
+        }
+    }
+
+    pub fn style<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&T::Output) -> Style + 'a,
+    {
+        self.value_style = Some(Box::new(f));
+        self
+    }
+
+    pub fn highlight<F>(mut self, f: F) -> Self
+    where
+        F: Fn(Point, &T::Output) -> Option<Style> + 'a,
+    {
+        self.highlights.push(Box::new(f));
+        self
+    }
+
+    fn overlay(&mut self, point: Point, char: Option<char>, style: Style) {
+        let overlay = self.overlays.entry(point).or_default();
+        if char.is_some() {
+            overlay.char = char;
+        }
+        overlay.style = overlay.style.merge(style);
+    }
+
+    pub fn points<I>(mut self, points: I, style: Style) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Borrow<Point>,
+    {
+        for point in points {
+            self.overlay(*point.borrow(), None, style);
+        }
+        self
+    }
+
+    pub fn mark<I>(mut self, points: I, char: char, style: Style) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Borrow<Point>,
+    {
+        for point in points {
+            self.overlay(*point.borrow(), Some(char), style);
+        }
+        self
+    }
+
+    pub fn directions<I>(mut self, steps: I, style: Style) -> Self
+    where
+        I: IntoIterator<Item = (Point, Direction)>,
+    {
+        for (point, direction) in steps {
+            self.overlay(point, Some(direction.char()), style);
+        }
+        self
+    }
+
+    // Draws an arrow on each point towards the next one, the final point keeps its value
+    pub fn path<I>(mut self, points: I, style: Style) -> Self
+    where
+        I: IntoIterator<Item = Point>,
+    {
+        let mut points = points.into_iter().peekable();
+        while let Some(point) = points.next() {
+            let char = points
+                .peek()
+                .and_then(|next| direction_between(&point, next))
+                .map(|direction| direction.char());
+            self.overlay(point, char, style);
+        }
+        self
+    }
+
+    pub fn rulers(mut self) -> Self {
+        self.rulers = true;
+        self
+    }
+
+    pub fn viewport(mut self, origin: Point, width: usize, height: usize) -> Self {
+        self.viewport = Some((origin, width, height));
+        self
+    }
+
+    pub fn plain(mut self) -> Self {
+        self.colour = false;
+        self
+    }
+
+    fn bounds(&self) -> (std::ops::Range<usize>, std::ops::Range<usize>) {
+        let (width, height) = (self.grid.width(), self.grid.height());
+        match self.viewport {
+            Some((origin, w, h)) => (
+                origin.x.min(width)..(origin.x + w).min(width),
+                origin.y.min(height)..(origin.y + h).min(height),
+            ),
+            None => (0..width, 0..height),
+        }
+    }
+
+    fn cell_style(&self, point: Point, value: &T::Output) -> Style {
+        let mut style = match &self.value_style {
+            Some(f) => f(value),
+            None => Style::new(),
+        };
+        for highlight in &self.highlights {
+            if let Some(s) = highlight(point, value) {
+                style = style.merge(s);
+            }
+        }
+        style
+    }
+}
+
+fn direction_between(from: &Point, to: &Point) -> Option<Direction> {
+    Direction::all()
+        .iter()
+        .find(|&&direction| *from + direction == Some(*to))
+        .copied()
+}
+
+impl<T> Display for GridRenderer<'_, T>
+where
+    T: Index<usize>,
+    T::Output: Display,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (xs, ys) = self.bounds();
+        let margin = ys.end.saturating_sub(1).to_string().len();
+
+        if self.rulers {
+            let digits = xs.end.saturating_sub(1).to_string().len();
+            for d in (0..digits).rev() {
+                write!(f, "{:margin$} ", "")?;
+                for x in xs.clone() {
+                    let n = x / 10usize.pow(d as u32);
+                    if n == 0 && d != 0 {
+                        f.write_char(' ')?;
+                    } else {
+                        write!(f, "{}", n % 10)?;
+                    }
+                }
+                f.write_char('\n')?;
+            }
+        }
+
+        for (i, y) in ys.enumerate() {
+            if i != 0 {
+                f.write_char('\n')?;
+            }
+            if self.rulers {
+                write!(f, "{y:>margin$} ")?;
+            }
+            for x in xs.clone() {
+                let point = Point::new(x, y);
+                let value = self.grid.value_at(x, y).unwrap();
+                let mut style = self.cell_style(point, &value);
+
+                let text = match self.overlays.get(&point) {
+                    Some(overlay) => {
+                        style = style.merge(overlay.style);
+                        match overlay.char {
+                            Some(c) => c.to_string(),
+                            None => value.to_string(),
+                        }
+                    }
+                    None => value.to_string(),
+                };
+
+                if self.colour {
+                    f.write_str(&style.paint(&text))?;
+                } else {
+                    f.write_str(&text)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_overlays() {
+        let grid: Grid<Vec<char>> = "...\n.#.\n...".parse().unwrap();
+        let rendered = grid
+            .render()
+            .path(
+                [Point::new(0, 0), Point::new(1, 0), Point::new(2, 0)],
+                Style::new(),
+            )
+            .mark([Point::new(1, 2)], 'O', Style::fg(Color::Red))
+            .plain()
+            .to_string();
+
+        assert_eq!(rendered, ">>.\n.#.\n.O.");
+        assert_eq!(grid.to_string(), "...\n.#.\n...");
+    }
+
+    #[test]
+    fn test_rulers_and_viewport() {
+        let grid: Grid<Vec<char>> = (0..12)
+            .map(|_| "............")
+            .collect::<Vec<_>>()
+            .join("\n")
+            .parse()
+            .unwrap();
+        let rendered = grid
+            .render()
+            .viewport(Point::new(8, 9), 4, 2)
+            .rulers()
+            .plain()
+            .to_string();
+
+        assert_eq!(rendered, "     11\n   8901\n 9 ....\n10 ....");
+    }
+}
@@ -0,0 +1,278 @@
+use std::{fmt::Display, str::FromStr};
+
+use nom::Parser;
+
+use super::{
+    grid::Grid,
+    parse::{parse_all, ParseError},
+};
+
+// Line and column are 1-based and count from the start of the whole input
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl Display for InputError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Input error at line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+impl std::error::Error for InputError {}
+
+// Puzzle input with \r\n turned into \n and trailing whitespace dropped
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Input {
+    text: String,
+}
+
+impl Input {
+    pub fn new(text: &str) -> Self {
+        Self {
+            text: text.replace("\r\n", "\n").trim_end().to_string(),
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    pub fn all(&self) -> Section<'_> {
+        Section {
+            text: &self.text,
+            first_line: 1,
+        }
+    }
+
+    // Runs of lines separated by one or more blank lines
+    pub fn sections(&self) -> Vec<Section<'_>> {
+        let mut out = vec![];
+        let mut start = None;
+        let mut offset = 0;
+
+        for (i, line) in self.text.split('\n').enumerate() {
+            if line.trim().is_empty() {
+                if let Some((first_line, from)) = start.take() {
+                    out.push(Section {
+                        text: &self.text[from..offset - 1],
+                        first_line,
+                    });
+                }
+            } else if start.is_none() {
+                start = Some((i + 1, offset));
+            }
+            offset += line.len() + 1;
+        }
+        if let Some((first_line, from)) = start {
+            out.push(Section {
+                text: &self.text[from..],
+                first_line,
+            });
+        }
+        out
+    }
+
+    pub fn sections2(&self) -> Result<(Section<'_>, Section<'_>), InputError> {
+        let [a, b] = self.sections_n()?;
+        Ok((a, b))
+    }
+
+    pub fn sections3(&self) -> Result<(Section<'_>, Section<'_>, Section<'_>), InputError> {
+        let [a, b, c] = self.sections_n()?;
+        Ok((a, b, c))
+    }
+
+    fn sections_n<const N: usize>(&self) -> Result<[Section<'_>; N], InputError> {
+        let sections = self.sections();
+        let found = sections.len();
+        sections.try_into().map_err(|_| InputError {
+            line: 1,
+            column: 1,
+            message: format!("expected {N} sections separated by blank lines, found {found}"),
+        })
+    }
+
+    pub fn lines(&self) -> impl Iterator<Item = &str> {
+        self.text.lines()
+    }
+
+    pub fn grid<U>(&self) -> Result<Grid<Vec<U>>, InputError>
+    where
+        U: FromStr,
+        U::Err: Display,
+    {
+        self.all().grid()
+    }
+
+    pub fn numbers<T>(&self) -> Result<Vec<T>, InputError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        self.all().numbers()
+    }
+}
+
+impl From<String> for Input {
+    fn from(text: String) -> Self {
+        Self::new(&text)
+    }
+}
+
+impl From<&str> for Input {
+    fn from(text: &str) -> Self {
+        Self::new(text)
+    }
+}
+
+// Part of an Input which remembers where it started, so errors point into the whole file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Section<'a> {
+    text: &'a str,
+    first_line: usize,
+}
+
+impl<'a> Section<'a> {
+    pub fn text(&self) -> &'a str {
+        self.text
+    }
+
+    pub fn first_line(&self) -> usize {
+        self.first_line
+    }
+
+    pub fn lines(&self) -> impl Iterator<Item = &'a str> {
+        self.text.lines()
+    }
+
+    fn error(&self, line: usize, column: usize, message: String) -> InputError {
+        InputError {
+            line: self.first_line + line,
+            column,
+            message,
+        }
+    }
+
+    // Each line through FromStr, such as an AocParse struct
+    pub fn parse_lines<T>(&self) -> Result<Vec<T>, InputError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        self.lines()
+            .enumerate()
+            .map(|(i, line)| line.parse().map_err(|e| self.error(i, 1, format!("{e}"))))
+            .collect()
+    }
+
+    // A nom parser over the whole section, see parse::parse_all
+    pub fn parse_with<O, P>(&self, parser: P) -> Result<O, InputError>
+    where
+        P: Parser<&'a str, O, nom::error::Error<&'a str>>,
+    {
+        parse_all(parser, self.text).map_err(|e: ParseError| {
+            self.error(e.line - 1, e.column, e.kind.description().to_string())
+        })
+    }
+
+    // One cell per char, all rows must be the same width
+    pub fn grid<U>(&self) -> Result<Grid<Vec<U>>, InputError>
+    where
+        U: FromStr,
+        U::Err: Display,
+    {
+        let width = self.lines().next().map_or(0, |l| l.chars().count());
+        let mut items = vec![];
+
+        for (y, line) in self.lines().enumerate() {
+            if line.chars().count() != width {
+                return Err(self.error(
+                    y,
+                    1,
+                    format!("row is {} wide, expected {width}", line.chars().count()),
+                ));
+            }
+            for (x, cell) in line.split_inclusive(|_| true).enumerate() {
+                items.push(
+                    cell.parse()
+                        .map_err(|e| self.error(y, x + 1, format!("{cell:?}: {e}")))?,
+                );
+            }
+        }
+
+        let height = items.len().checked_div(width).unwrap_or(0);
+        Ok(Grid::new(width, height, items))
+    }
+
+    // Every integer in the section, with a - directly before the digits making it negative
+    pub fn numbers<T>(&self) -> Result<Vec<T>, InputError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let mut out = vec![];
+        for (y, line) in self.lines().enumerate() {
+            let bytes = line.as_bytes();
+            let mut i = 0;
+            while i < bytes.len() {
+                if !bytes[i].is_ascii_digit() {
+                    i += 1;
+                    continue;
+                }
+                let start = if i > 0 && bytes[i - 1] == b'-' {
+                    i - 1
+                } else {
+                    i
+                };
+                while i < bytes.len() && bytes[i].is_ascii_digit() {
+                    i += 1;
+                }
+                let number = &line[start..i];
+                out.push(number.parse().map_err(|e| {
+                    let column = line[..start].chars().count() + 1;
+                    self.error(y, column, format!("{number:?}: {e}"))
+                })?);
+            }
+        }
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_sections() {
+        let input = Input::new("47|53\r\n97|13\r\n\r\n  \r\n75,47\r\n61,13\r\n\r\n");
+        let (rules, updates) = input.sections2().unwrap();
+
+        assert_eq!(rules.text(), "47|53\n97|13");
+        assert_eq!((updates.text(), updates.first_line()), ("75,47\n61,13", 5));
+        assert_eq!(updates.numbers::<usize>().unwrap(), vec![75, 47, 61, 13]);
+        assert!(input.sections3().is_err());
+    }
+
+    #[test]
+    fn test_errors() {
+        let input = Input::new("p=0,4 v=3,-3\n\n#.\n#");
+        let (robots, map) = input.sections2().unwrap();
+
+        assert_eq!(robots.numbers::<i8>().unwrap(), vec![0, 4, 3, -3]);
+        let err = robots.numbers::<u8>().unwrap_err();
+        assert_eq!((err.line, err.column), (1, 11));
+
+        let err = map.grid::<char>().err().unwrap();
+        assert_eq!((err.line, err.column), (4, 1));
+
+        let err = map.parse_lines::<u8>().unwrap_err();
+        assert_eq!(err.line, 3);
+    }
+}
@@ -0,0 +1,457 @@
+use std::{
+    collections::BTreeMap,
+    ops::{Add, Range, Sub},
+};
+
+use crate::point2d::Point2D;
+
+// Disjoint half-open ranges, touching ranges are merged together
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IntervalSet<N> {
+    ranges: BTreeMap<N, N>,
+}
+
+impl<N> Default for IntervalSet<N> {
+    fn default() -> Self {
+        Self {
+            ranges: BTreeMap::new(),
+        }
+    }
+}
+
+impl<N: Ord + Copy> IntervalSet<N> {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.ranges.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Range<N>> + '_ {
+        self.ranges.iter().map(|(&start, &end)| start..end)
+    }
+
+    pub fn first(&self) -> Option<Range<N>> {
+        self.ranges
+            .first_key_value()
+            .map(|(&start, &end)| start..end)
+    }
+
+    pub fn last(&self) -> Option<Range<N>> {
+        self.ranges
+            .last_key_value()
+            .map(|(&start, &end)| start..end)
+    }
+
+    pub fn insert(&mut self, range: Range<N>) {
+        if range.start >= range.end {
+            return;
+        }
+        let (mut start, mut end) = (range.start, range.end);
+
+        if let Some((&s, &e)) = self.ranges.range(..=start).next_back() {
+            if e >= start {
+                start = s;
+                end = end.max(e);
+            }
+        }
+
+        let swallowed: Vec<_> = self
+            .ranges
+            .range(start..=end)
+            .map(|(&s, &e)| (s, e))
+            .collect();
+        for (s, e) in swallowed {
+            self.ranges.remove(&s);
+            end = end.max(e);
+        }
+
+        self.ranges.insert(start, end);
+    }
+
+    pub fn remove(&mut self, range: Range<N>) {
+        if range.start >= range.end {
+            return;
+        }
+
+        for overlap in self.overlapping(&range).collect::<Vec<_>>() {
+            self.ranges.remove(&overlap.start);
+            if overlap.start < range.start {
+                self.ranges.insert(overlap.start, range.start);
+            }
+            if overlap.end > range.end {
+                self.ranges.insert(range.end, overlap.end);
+            }
+        }
+    }
+
+    pub fn contains(&self, n: &N) -> bool {
+        self.containing(n).is_some()
+    }
+
+    pub fn containing(&self, n: &N) -> Option<Range<N>> {
+        self.ranges
+            .range(..=n)
+            .next_back()
+            .filter(|(_, &end)| end > *n)
+            .map(|(&start, &end)| start..end)
+    }
+
+    pub fn overlapping<'a>(&'a self, range: &Range<N>) -> impl Iterator<Item = Range<N>> + 'a {
+        let before = self
+            .ranges
+            .range(..range.start)
+            .next_back()
+            .filter(|(_, &end)| end > range.start);
+        let within = self.ranges.range(range.start..range.end);
+        before
+            .into_iter()
+            .chain(within)
+            .map(|(&start, &end)| start..end)
+    }
+
+    pub fn overlaps(&self, range: &Range<N>) -> bool {
+        range.start < range.end && self.overlapping(range).next().is_some()
+    }
+
+    // Everything below `at` and everything from `at` upwards
+    pub fn split(&self, at: N) -> (Self, Self) {
+        let mut below = Self::new();
+        let mut above = Self::new();
+        for range in self.iter() {
+            if range.end <= at {
+                below.insert(range);
+            } else if range.start >= at {
+                above.insert(range);
+            } else {
+                below.insert(range.start..at);
+                above.insert(at..range.end);
+            }
+        }
+        (below, above)
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut out = self.clone();
+        for range in other.iter() {
+            out.insert(range);
+        }
+        out
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut out = Self::new();
+        for range in self.iter() {
+            for overlap in other.overlapping(&range) {
+                out.insert(overlap.start.max(range.start)..overlap.end.min(range.end));
+            }
+        }
+        out
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut out = self.clone();
+        for range in other.iter() {
+            out.remove(range);
+        }
+        out
+    }
+
+    // The spaces between consecutive ranges
+    pub fn gaps(&self) -> impl Iterator<Item = Range<N>> + '_ {
+        self.ranges
+            .iter()
+            .zip(self.ranges.iter().skip(1))
+            .map(|((_, &end), (&start, _))| end..start)
+    }
+}
+
+impl<N> IntervalSet<N>
+where
+    N: Ord + Copy + Sub<Output = N>,
+{
+    // The lowest range at least `len` long that starts before `before`
+    pub fn first_fit(&self, len: N, before: Option<N>) -> Option<Range<N>> {
+        self.iter()
+            .take_while(|range| before.is_none_or(|before| range.start < before))
+            .find(|range| range.end - range.start >= len)
+    }
+
+    pub fn covered<S>(&self) -> S
+    where
+        S: std::iter::Sum<N>,
+    {
+        self.iter().map(|range| range.end - range.start).sum()
+    }
+}
+
+impl<N: Ord + Copy> FromIterator<Range<N>> for IntervalSet<N> {
+    fn from_iter<I: IntoIterator<Item = Range<N>>>(iter: I) -> Self {
+        let mut out = Self::new();
+        for range in iter {
+            out.insert(range);
+        }
+        out
+    }
+}
+
+// Disjoint half-open ranges each carrying a value, touching ranges with equal values are merged
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IntervalMap<N, V> {
+    ranges: BTreeMap<N, (N, V)>,
+}
+
+impl<N, V> Default for IntervalMap<N, V> {
+    fn default() -> Self {
+        Self {
+            ranges: BTreeMap::new(),
+        }
+    }
+}
+
+impl<N, V> IntervalMap<N, V>
+where
+    N: Ord + Copy,
+    V: Clone + PartialEq,
+{
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.ranges.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Range<N>, &V)> + '_ {
+        self.ranges
+            .iter()
+            .map(|(&start, (end, value))| (start..*end, value))
+    }
+
+    pub fn get(&self, n: &N) -> Option<&V> {
+        self.ranges
+            .range(..=n)
+            .next_back()
+            .filter(|(_, (end, _))| end > n)
+            .map(|(_, (_, value))| value)
+    }
+
+    fn overlapping_starts(&self, range: &Range<N>) -> Vec<N> {
+        let before = self
+            .ranges
+            .range(..range.start)
+            .next_back()
+            .filter(|(_, (end, _))| *end > range.start)
+            .map(|(&start, _)| start);
+        before
+            .into_iter()
+            .chain(self.ranges.range(range.start..range.end).map(|(&s, _)| s))
+            .collect()
+    }
+
+    pub fn remove(&mut self, range: Range<N>) {
+        if range.start >= range.end {
+            return;
+        }
+        for start in self.overlapping_starts(&range) {
+            let (end, value) = self.ranges.remove(&start).unwrap();
+            if start < range.start {
+                self.ranges.insert(start, (range.start, value.clone()));
+            }
+            if end > range.end {
+                self.ranges.insert(range.end, (end, value));
+            }
+        }
+    }
+
+    // Overwrites whatever was there before
+    pub fn insert(&mut self, range: Range<N>, value: V) {
+        if range.start >= range.end {
+            return;
+        }
+        self.remove(range.clone());
+        let (mut start, mut end) = (range.start, range.end);
+
+        if let Some((&s, (e, v))) = self.ranges.range(..start).next_back() {
+            if *e == start && *v == value {
+                start = s;
+            }
+        }
+        if let Some((e, v)) = self.ranges.get(&end) {
+            if *v == value {
+                let e = *e;
+                self.ranges.remove(&end);
+                end = e;
+            }
+        }
+        self.ranges.insert(start, (end, value));
+    }
+
+    pub fn keys(&self) -> IntervalSet<N> {
+        self.iter().map(|(range, _)| range).collect()
+    }
+}
+
+// Maps each distinct coordinate onto 0..len so huge sparse inputs fit in a small grid
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Compression<N> {
+    values: Vec<N>,
+}
+
+impl<N: Ord + Copy> Compression<N> {
+    pub fn new<I: IntoIterator<Item = N>>(values: I) -> Self {
+        let mut values: Vec<N> = values.into_iter().collect();
+        values.sort();
+        values.dedup();
+        Self { values }
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn index(&self, value: &N) -> Option<usize> {
+        self.values.binary_search(value).ok()
+    }
+
+    // Index of the greatest value that's <= the given one
+    pub fn floor_index(&self, value: &N) -> Option<usize> {
+        match self.values.binary_search(value) {
+            Ok(i) => Some(i),
+            Err(0) => None,
+            Err(i) => Some(i - 1),
+        }
+    }
+
+    pub fn value(&self, index: usize) -> Option<N> {
+        self.values.get(index).copied()
+    }
+
+    pub fn values(&self) -> &[N] {
+        &self.values
+    }
+}
+
+impl<N> Compression<N>
+where
+    N: Ord + Copy + Add<Output = N>,
+{
+    // Adds value + step for every value so the spaces between coordinates get their own index
+    pub fn with_gaps<I: IntoIterator<Item = N>>(values: I, step: N) -> Self {
+        Self::new(values.into_iter().flat_map(|v| [v, v + step]))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Compression2D<N> {
+    pub x: Compression<N>,
+    pub y: Compression<N>,
+}
+
+impl<N: Ord + Copy> Compression2D<N> {
+    pub fn new<'a, I>(points: I) -> Self
+    where
+        I: IntoIterator<Item = &'a Point2D<N>>,
+        N: 'a,
+    {
+        let points: Vec<_> = points.into_iter().collect();
+        Self {
+            x: Compression::new(points.iter().map(|p| p.x)),
+            y: Compression::new(points.iter().map(|p| p.y)),
+        }
+    }
+
+    pub fn dims(&self) -> (usize, usize) {
+        (self.x.len(), self.y.len())
+    }
+
+    pub fn index(&self, point: &Point2D<N>) -> Option<(usize, usize)> {
+        Some((self.x.index(&point.x)?, self.y.index(&point.y)?))
+    }
+
+    pub fn value(&self, (x, y): (usize, usize)) -> Option<Point2D<N>> {
+        Some(Point2D::new(self.x.value(x)?, self.y.value(y)?))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_interval_set() {
+        let mut set: IntervalSet<i32> = [0..5, 10..15, 5..7].into_iter().collect();
+        assert_eq!(set.iter().collect::<Vec<_>>(), [0..7, 10..15]);
+
+        set.insert(6..11);
+        assert_eq!(set.len(), 1);
+        assert_eq!(set.first(), Some(0..15));
+
+        set.remove(3..5);
+        set.remove(12..20);
+        assert_eq!(set.iter().collect::<Vec<_>>(), [0..3, 5..12]);
+        assert!(set.contains(&11));
+        assert!(!set.contains(&3));
+        assert_eq!(set.covered::<i32>(), 10);
+
+        assert_eq!(set.first_fit(4, None), Some(5..12));
+        assert_eq!(set.first_fit(4, Some(5)), None);
+        assert_eq!(set.gaps().next(), Some(3..5));
+
+        let (below, above) = set.split(8);
+        assert_eq!(below.iter().collect::<Vec<_>>(), [0..3, 5..8]);
+        assert_eq!(above.len(), 1);
+        assert_eq!(above.first(), Some(8..12));
+
+        let other: IntervalSet<i32> = [2..6, 11..20].into_iter().collect();
+        assert_eq!(
+            set.intersection(&other).iter().collect::<Vec<_>>(),
+            [2..3, 5..6, 11..12]
+        );
+        assert_eq!(set.union(&other).last(), Some(0..20));
+        assert_eq!(
+            set.difference(&other).iter().collect::<Vec<_>>(),
+            [0..2, 6..11]
+        );
+    }
+
+    #[test]
+    fn test_interval_map() {
+        let mut map = IntervalMap::new();
+        map.insert(0..10, 'a');
+        map.insert(3..5, 'b');
+        map.insert(5..6, 'b');
+        assert_eq!(
+            map.iter().collect::<Vec<_>>(),
+            [(0..3, &'a'), (3..6, &'b'), (6..10, &'a')]
+        );
+        assert_eq!(map.get(&4), Some(&'b'));
+        assert_eq!(map.get(&10), None);
+
+        map.insert(3..6, 'a');
+        assert_eq!(map.iter().collect::<Vec<_>>(), [(0..10, &'a')]);
+    }
+
+    #[test]
+    fn test_compression() {
+        let points = [(5, 1_000_000), (-3, 7), (5, 7)].map(Point2D::from);
+        let compression = Compression2D::new(&points);
+        assert_eq!(compression.dims(), (2, 2));
+        assert_eq!(compression.index(&points[0]), Some((1, 1)));
+        assert_eq!(compression.value((0, 0)), Some(Point2D::new(-3, 7)));
+        assert_eq!(compression.x.floor_index(&0), Some(0));
+    }
+}
@@ -0,0 +1,529 @@
+use std::{
+    fmt::Display,
+    ops::{Add, Div, Index, IndexMut, Mul, Neg, Sub},
+};
+
+use crate::quant::{checked_gcd, NumConsts};
+
+// Every operation is checked, anything that doesn't fit in an i128 is reported as this
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Overflow;
+
+impl Display for Overflow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "arithmetic overflow")
+    }
+}
+
+impl std::error::Error for Overflow {}
+
+// Always stored in lowest terms with a positive denominator
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rational {
+    num: i128,
+    den: i128,
+}
+
+fn gcd_abs(a: i128, b: i128) -> Result<i128, Overflow> {
+    checked_gcd(a, b).ok_or(Overflow)
+}
+
+impl Rational {
+    pub const ZERO: Rational = Rational { num: 0, den: 1 };
+    pub const ONE: Rational = Rational { num: 1, den: 1 };
+
+    pub fn new(num: i128, den: i128) -> Self {
+        Self::checked_new(num, den).expect("Rational overflow")
+    }
+
+    pub fn checked_new(num: i128, den: i128) -> Result<Self, Overflow> {
+        assert!(den != 0, "Rational with a zero denominator");
+        let g = gcd_abs(num, den)?;
+        let (num, den) = (num / g, den / g);
+        if den < 0 {
+            Ok(Self {
+                num: num.checked_neg().ok_or(Overflow)?,
+                den: den.checked_neg().ok_or(Overflow)?,
+            })
+        } else {
+            Ok(Self { num, den })
+        }
+    }
+
+    pub const fn integer(n: i128) -> Self {
+        Self { num: n, den: 1 }
+    }
+
+    pub fn numer(&self) -> i128 {
+        self.num
+    }
+
+    pub fn denom(&self) -> i128 {
+        self.den
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.num == 0
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.den == 1
+    }
+
+    pub fn to_integer(&self) -> Option<i128> {
+        self.is_integer().then_some(self.num)
+    }
+
+    pub fn signum(&self) -> i128 {
+        self.num.signum()
+    }
+
+    pub fn floor(&self) -> i128 {
+        self.num.div_euclid(self.den)
+    }
+
+    pub fn ceil(&self) -> i128 {
+        -(-self.num).div_euclid(self.den)
+    }
+
+    pub fn recip(&self) -> Self {
+        Self::new(self.den, self.num)
+    }
+
+    pub fn checked_neg(self) -> Result<Self, Overflow> {
+        Ok(Self {
+            num: self.num.checked_neg().ok_or(Overflow)?,
+            den: self.den,
+        })
+    }
+
+    pub fn checked_add(self, other: Self) -> Result<Self, Overflow> {
+        // Only scale by what the denominators don't already share
+        let g = gcd_abs(self.den, other.den)?;
+        let lhs = self.num.checked_mul(other.den / g).ok_or(Overflow)?;
+        let rhs = other.num.checked_mul(self.den / g).ok_or(Overflow)?;
+        let num = lhs.checked_add(rhs).ok_or(Overflow)?;
+        let den = (self.den / g).checked_mul(other.den).ok_or(Overflow)?;
+        Self::checked_new(num, den)
+    }
+
+    pub fn checked_sub(self, other: Self) -> Result<Self, Overflow> {
+        self.checked_add(other.checked_neg()?)
+    }
+
+    pub fn checked_mul(self, other: Self) -> Result<Self, Overflow> {
+        // Cross cancel first so the intermediate products stay small
+        let g1 = gcd_abs(self.num, other.den)?;
+        let g2 = gcd_abs(other.num, self.den)?;
+        let num = (self.num / g1)
+            .checked_mul(other.num / g2)
+            .ok_or(Overflow)?;
+        let den = (self.den / g2)
+            .checked_mul(other.den / g1)
+            .ok_or(Overflow)?;
+        Self::checked_new(num, den)
+    }
+
+    pub fn checked_div(self, other: Self) -> Result<Self, Overflow> {
+        assert!(!other.is_zero(), "Rational division by zero");
+        self.checked_mul(Self::checked_new(other.den, other.num)?)
+    }
+}
+
+impl Default for Rational {
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
+impl NumConsts for Rational {
+    const ZERO: Self = Rational::ZERO;
+    const ONE: Self = Rational::ONE;
+}
+
+macro_rules! impl_from_int {
+    ($typ:ty) => {
+        impl From<$typ> for Rational {
+            fn from(n: $typ) -> Self {
+                Self::integer(n as i128)
+            }
+        }
+    };
+}
+impl_from_int!(i8);
+impl_from_int!(i16);
+impl_from_int!(i32);
+impl_from_int!(i64);
+impl_from_int!(i128);
+impl_from_int!(isize);
+impl_from_int!(u8);
+impl_from_int!(u16);
+impl_from_int!(u32);
+impl_from_int!(u64);
+
+impl TryFrom<usize> for Rational {
+    type Error = Overflow;
+
+    fn try_from(n: usize) -> Result<Self, Self::Error> {
+        i128::try_from(n).map(Self::integer).map_err(|_| Overflow)
+    }
+}
+
+macro_rules! impl_op {
+    ($trait:ident, $fn:ident, $checked:ident) => {
+        impl $trait for Rational {
+            type Output = Rational;
+
+            fn $fn(self, rhs: Self) -> Self::Output {
+                self.$checked(rhs).expect("Rational overflow")
+            }
+        }
+    };
+}
+impl_op!(Add, add, checked_add);
+impl_op!(Sub, sub, checked_sub);
+impl_op!(Mul, mul, checked_mul);
+impl_op!(Div, div, checked_div);
+
+impl Neg for Rational {
+    type Output = Rational;
+
+    fn neg(self) -> Self::Output {
+        self.checked_neg().expect("Rational overflow")
+    }
+}
+
+impl Display for Rational {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_integer() {
+            write!(f, "{}", self.num)
+        } else {
+            write!(f, "{}/{}", self.num, self.den)
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Matrix {
+    rows: usize,
+    cols: usize,
+    items: Vec<Rational>,
+}
+
+impl Matrix {
+    pub fn new(rows: usize, cols: usize, items: Vec<Rational>) -> Self {
+        assert_eq!(
+            rows * cols,
+            items.len(),
+            "Matrix items don't match its dimensions"
+        );
+        Self { rows, cols, items }
+    }
+
+    pub fn zeros(rows: usize, cols: usize) -> Self {
+        Self::new(rows, cols, vec![Rational::ZERO; rows * cols])
+    }
+
+    pub fn identity(size: usize) -> Self {
+        let mut out = Self::zeros(size, size);
+        for i in 0..size {
+            out[(i, i)] = Rational::ONE;
+        }
+        out
+    }
+
+    pub fn from_rows<R, N>(rows: impl IntoIterator<Item = R>) -> Self
+    where
+        R: IntoIterator<Item = N>,
+        N: Into<Rational>,
+    {
+        let rows: Vec<Vec<Rational>> = rows
+            .into_iter()
+            .map(|row| row.into_iter().map(Into::into).collect())
+            .collect();
+        let cols = rows.first().map_or(0, Vec::len);
+        assert!(
+            rows.iter().all(|row| row.len() == cols),
+            "Matrix rows have different lengths"
+        );
+        Self::new(rows.len(), cols, rows.into_iter().flatten().collect())
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn row(&self, row: usize) -> &[Rational] {
+        &self.items[row * self.cols..(row + 1) * self.cols]
+    }
+
+    pub fn transpose(&self) -> Self {
+        let mut out = Self::zeros(self.cols, self.rows);
+        for r in 0..self.rows {
+            for c in 0..self.cols {
+                out[(c, r)] = self[(r, c)];
+            }
+        }
+        out
+    }
+
+    // The matrix with `column` appended on the right, as used for [A | b]
+    pub fn augment(&self, column: &[Rational]) -> Self {
+        assert_eq!(column.len(), self.rows, "Column doesn't match matrix rows");
+        let items = (0..self.rows)
+            .flat_map(|r| self.row(r).iter().chain(Some(&column[r])).copied())
+            .collect();
+        Self::new(self.rows, self.cols + 1, items)
+    }
+
+    pub fn checked_mul(&self, other: &Matrix) -> Result<Matrix, Overflow> {
+        assert_eq!(self.cols, other.rows, "Matrix dimensions don't agree");
+        let mut out = Self::zeros(self.rows, other.cols);
+        for r in 0..self.rows {
+            for c in 0..other.cols {
+                out[(r, c)] = (0..self.cols).try_fold(Rational::ZERO, |acc, k| {
+                    acc.checked_add(self[(r, k)].checked_mul(other[(k, c)])?)
+                })?;
+            }
+        }
+        Ok(out)
+    }
+
+    pub fn mul_vec(&self, v: &[Rational]) -> Result<Vec<Rational>, Overflow> {
+        assert_eq!(self.cols, v.len(), "Vector doesn't match matrix columns");
+        (0..self.rows)
+            .map(|r| {
+                self.row(r)
+                    .iter()
+                    .zip(v)
+                    .try_fold(Rational::ZERO, |acc, (&a, &b)| {
+                        acc.checked_add(a.checked_mul(b)?)
+                    })
+            })
+            .collect()
+    }
+
+    fn swap_rows(&mut self, a: usize, b: usize) {
+        if a != b {
+            for c in 0..self.cols {
+                self.items.swap(a * self.cols + c, b * self.cols + c);
+            }
+        }
+    }
+
+    // Reduced row echelon form, along with the column of each pivot in row order
+    pub fn rref(&self) -> Result<(Matrix, Vec<usize>), Overflow> {
+        let mut m = self.clone();
+        let mut pivots = vec![];
+
+        for c in 0..m.cols {
+            let row = pivots.len();
+            if row == m.rows {
+                break;
+            }
+            let Some(p) = (row..m.rows).find(|&r| !m[(r, c)].is_zero()) else {
+                continue;
+            };
+            m.swap_rows(row, p);
+
+            let pivot = m[(row, c)];
+            for k in c..m.cols {
+                m[(row, k)] = m[(row, k)].checked_div(pivot)?;
+            }
+            for r in (0..m.rows).filter(|&r| r != row) {
+                let factor = m[(r, c)];
+                if factor.is_zero() {
+                    continue;
+                }
+                for k in c..m.cols {
+                    m[(r, k)] = m[(r, k)].checked_sub(factor.checked_mul(m[(row, k)])?)?;
+                }
+            }
+            pivots.push(c);
+        }
+
+        Ok((m, pivots))
+    }
+
+    pub fn rank(&self) -> Result<usize, Overflow> {
+        self.rref().map(|(_, pivots)| pivots.len())
+    }
+
+    pub fn determinant(&self) -> Result<Rational, Overflow> {
+        assert_eq!(self.rows, self.cols, "Determinant of a non-square matrix");
+        let mut m = self.clone();
+        let mut det = Rational::ONE;
+
+        for c in 0..m.cols {
+            let Some(p) = (c..m.rows).find(|&r| !m[(r, c)].is_zero()) else {
+                return Ok(Rational::ZERO);
+            };
+            if p != c {
+                m.swap_rows(c, p);
+                det = det.checked_neg()?;
+            }
+            let pivot = m[(c, c)];
+            det = det.checked_mul(pivot)?;
+            for r in c + 1..m.rows {
+                let factor = m[(r, c)].checked_div(pivot)?;
+                for k in c..m.cols {
+                    m[(r, k)] = m[(r, k)].checked_sub(factor.checked_mul(m[(c, k)])?)?;
+                }
+            }
+        }
+
+        Ok(det)
+    }
+
+    // Solve self * x = b
+    pub fn solve(&self, b: &[Rational]) -> Result<Solution, Overflow> {
+        let (m, pivots) = self.augment(b).rref()?;
+        if pivots.last() == Some(&self.cols) {
+            return Ok(Solution::None);
+        }
+
+        let mut particular = vec![Rational::ZERO; self.cols];
+        for (row, &c) in pivots.iter().enumerate() {
+            particular[c] = m[(row, self.cols)];
+        }
+        if pivots.len() == self.cols {
+            return Ok(Solution::Unique(particular));
+        }
+
+        // One basis vector of the null space per free variable
+        let basis = (0..self.cols)
+            .filter(|c| !pivots.contains(c))
+            .map(|free| {
+                let mut v = vec![Rational::ZERO; self.cols];
+                v[free] = Rational::ONE;
+                for (row, &c) in pivots.iter().enumerate() {
+                    v[c] = m[(row, free)].checked_neg()?;
+                }
+                Ok(v)
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Solution::Infinite { particular, basis })
+    }
+}
+
+impl Index<(usize, usize)> for Matrix {
+    type Output = Rational;
+
+    fn index(&self, (row, col): (usize, usize)) -> &Self::Output {
+        assert!(
+            row < self.rows && col < self.cols,
+            "({row}, {col}) is outside of the matrix"
+        );
+        &self.items[row * self.cols + col]
+    }
+}
+
+impl IndexMut<(usize, usize)> for Matrix {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut Self::Output {
+        assert!(
+            row < self.rows && col < self.cols,
+            "({row}, {col}) is outside of the matrix"
+        );
+        &mut self.items[row * self.cols + col]
+    }
+}
+
+impl Display for Matrix {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for r in 0..self.rows {
+            let row: Vec<String> = self.row(r).iter().map(ToString::to_string).collect();
+            writeln!(f, "[{}]", row.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Solution {
+    Unique(Vec<Rational>),
+    None,
+    // Every solution is particular + a linear combination of basis
+    Infinite {
+        particular: Vec<Rational>,
+        basis: Vec<Vec<Rational>>,
+    },
+}
+
+impl Solution {
+    pub fn unique(&self) -> Option<&[Rational]> {
+        match self {
+            Solution::Unique(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    // The unique solution, only if every value in it is a whole number
+    pub fn integer(&self) -> Option<Vec<i128>> {
+        self.unique()?.iter().map(Rational::to_integer).collect()
+    }
+}
+
+// a * x + b * y = e, c * x + d * y = f
+pub fn solve_2x2(
+    [[a, b], [c, d]]: [[i128; 2]; 2],
+    [e, f]: [i128; 2],
+) -> Result<Solution, Overflow> {
+    Matrix::from_rows([[a, b], [c, d]]).solve(&[e.into(), f.into()])
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_rational() {
+        let r = Rational::new(6, -4);
+        assert_eq!((r.numer(), r.denom()), (-3, 2));
+        assert_eq!(r + Rational::new(1, 2), Rational::integer(-1));
+        assert_eq!(r * Rational::new(2, 3), Rational::integer(-1));
+        assert_eq!((r.floor(), r.ceil()), (-2, -1));
+        assert_eq!(Rational::new(1, 3).to_string(), "1/3");
+
+        let big = Rational::integer(i128::MAX);
+        assert_eq!(big.checked_add(Rational::ONE), Err(Overflow));
+        assert_eq!(big.checked_mul(Rational::new(1, 2)).unwrap().denom(), 2);
+    }
+
+    #[test]
+    fn test_solve() {
+        let m = Matrix::from_rows([[2, 1, -1], [-3, -1, 2], [-2, 1, 2]]);
+        let b = [8.into(), (-11).into(), (-3).into()];
+        assert_eq!(m.solve(&b).unwrap().integer(), Some(vec![2, 3, -1]));
+        assert_eq!(m.determinant().unwrap(), Rational::integer(-1));
+
+        let singular = Matrix::from_rows([[1, 2], [2, 4]]);
+        assert_eq!(singular.rank().unwrap(), 1);
+        assert_eq!(singular.determinant().unwrap(), Rational::ZERO);
+        assert_eq!(
+            singular.solve(&[1.into(), 3.into()]).unwrap(),
+            Solution::None
+        );
+        let Solution::Infinite { particular, basis } =
+            singular.solve(&[1.into(), 2.into()]).unwrap()
+        else {
+            panic!("Expected infinitely many solutions");
+        };
+        assert_eq!(particular, vec![Rational::ONE, Rational::ZERO]);
+        assert_eq!(basis, vec![vec![Rational::integer(-2), Rational::ONE]]);
+    }
+
+    #[test]
+    fn test_solve_2x2() {
+        let s = solve_2x2([[94, 22], [34, 67]], [8400, 5400]).unwrap();
+        assert_eq!(s.integer(), Some(vec![80, 40]));
+
+        let s = solve_2x2([[26, 67], [66, 21]], [12748, 12176]).unwrap();
+        assert!(s.unique().is_some());
+        assert_eq!(s.integer(), None);
+    }
+}
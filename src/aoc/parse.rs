@@ -1,3 +1,15 @@
+use std::fmt::Display;
+
+use nom::{
+    bytes::complete::tag,
+    character::complete::{char, digit1, line_ending, one_of, space0, space1},
+    combinator::{all_consuming, map_res, opt, recognize},
+    error::ErrorKind,
+    multi::separated_list1,
+    sequence::{delimited, pair, preceded, separated_pair, terminated, tuple},
+    Finish, IResult, Parser,
+};
+
 pub fn parse_number<T>(s: &str) -> nom::IResult<&str, T>
 where
     T: std::str::FromStr,
@@ -11,9 +23,157 @@ where
     )(s)
 }
 
+// Like parse_number, but also takes an explicit + as in "X+94"
+pub fn parse_signed<T>(s: &str) -> IResult<&str, T>
+where
+    T: std::str::FromStr,
+{
+    map_res(recognize(pair(opt(one_of("+-")), digit1)), str::parse::<T>)(s)
+}
+
+pub fn parse_unsigned<T>(s: &str) -> IResult<&str, T>
+where
+    T: std::str::FromStr,
+{
+    map_res(recognize(pair(opt(char('+')), digit1)), str::parse::<T>)(s)
+}
+
+// "1,2, 3" with optional spaces around the commas
+pub fn comma_separated<T>(s: &str) -> IResult<&str, Vec<T>>
+where
+    T: std::str::FromStr,
+{
+    separated_list1(delimited(space0, char(','), space0), parse_signed)(s)
+}
+
+pub fn space_separated<T>(s: &str) -> IResult<&str, Vec<T>>
+where
+    T: std::str::FromStr,
+{
+    separated_list1(space1, parse_signed)(s)
+}
+
+// "0,4" as in "p=0,4"
+pub fn number_pair<T>(s: &str) -> IResult<&str, (T, T)>
+where
+    T: std::str::FromStr,
+{
+    separated_pair(
+        parse_signed,
+        delimited(space0, char(','), space0),
+        parse_signed,
+    )(s)
+}
+
+// The value of a "key: value" line with a known key, as in "Register A: 729"
+pub fn labelled<'a, O, P>(key: &'a str, value: P) -> impl FnMut(&'a str) -> IResult<&'a str, O>
+where
+    P: Parser<&'a str, O, nom::error::Error<&'a str>>,
+{
+    preceded(tuple((tag(key), char(':'), space0)), value)
+}
+
+// Any "key: value" line, giving back both
+pub fn key_value<'a, O, P>(value: P) -> impl FnMut(&'a str) -> IResult<&'a str, (&'a str, O)>
+where
+    P: Parser<&'a str, O, nom::error::Error<&'a str>>,
+{
+    separated_pair(
+        nom::bytes::complete::is_not(":\r\n"),
+        pair(char(':'), space0),
+        value,
+    )
+}
+
+// Two labelled numbers such as "X+94, Y+34" or "x=3,y=-2", the labels include any "="
+pub fn labelled_coords<'a, T>(
+    x: &'a str,
+    y: &'a str,
+) -> impl FnMut(&'a str) -> IResult<&'a str, (T, T)>
+where
+    T: std::str::FromStr,
+{
+    separated_pair(
+        preceded(tag(x), parse_signed),
+        pair(char(','), space0),
+        preceded(tag(y), parse_signed),
+    )
+}
+
+pub fn lines_of<'a, O, P>(line: P) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>>
+where
+    P: Parser<&'a str, O, nom::error::Error<&'a str>>,
+{
+    separated_list1(line_ending, line)
+}
+
+// Blocks separated by a blank line
+pub fn blocks<'a, O, P>(block: P) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>>
+where
+    P: Parser<&'a str, O, nom::error::Error<&'a str>>,
+{
+    separated_list1(pair(line_ending, line_ending), block)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub source_line: String,
+    pub kind: ErrorKind,
+}
+
+impl ParseError {
+    // Where in input the unparsed remainder starts, both 1-based
+    fn at(input: &str, remaining: &str, kind: ErrorKind) -> Self {
+        let offset = input.len() - remaining.len();
+        let before = &input[..offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        let source_line = input[line_start..]
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .to_string();
+
+        Self {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            source_line,
+            kind,
+        }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Parse error at line {}, column {} ({})",
+            self.line,
+            self.column,
+            self.kind.description()
+        )?;
+        writeln!(f, "  {}", self.source_line)?;
+        write!(f, "  {}^", " ".repeat(self.column - 1))
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+// Runs parser over the whole input, only allowing trailing whitespace to be left over
+pub fn parse_all<'a, O, P>(parser: P, input: &'a str) -> Result<O, ParseError>
+where
+    P: Parser<&'a str, O, nom::error::Error<&'a str>>,
+{
+    all_consuming(terminated(parser, nom::character::complete::multispace0))(input)
+        .finish()
+        .map(|(_, out)| out)
+        .map_err(|e| ParseError::at(input, e.input, e.code))
+}
+
 #[cfg(test)]
 mod test {
-    use crate::aoc::parse::parse_number;
+    use crate::aoc::parse::*;
 
     #[test]
     fn test_parse() {
@@ -21,4 +181,45 @@ mod test {
 
         assert_eq!(parse_number::<isize>("-64").unwrap().1, -64isize);
     }
+
+    #[test]
+    fn test_combinators() {
+        assert_eq!(parse_signed::<i32>("+94").unwrap().1, 94);
+        assert!(parse_unsigned::<u32>("-1").is_err());
+        assert_eq!(comma_separated::<u8>("2,4, 1").unwrap().1, vec![2, 4, 1]);
+        assert_eq!(space_separated::<i64>("3 -4  5").unwrap().1, vec![3, -4, 5]);
+        assert_eq!(number_pair::<i32>("3,-3").unwrap().1, (3, -3));
+        assert_eq!(
+            labelled("Register A", parse_unsigned::<u64>)("Register A: 729")
+                .unwrap()
+                .1,
+            729
+        );
+        assert_eq!(
+            key_value(parse_signed::<i8>)("Prize: -3").unwrap().1,
+            ("Prize", -3)
+        );
+        assert_eq!(
+            labelled_coords::<i32>("X+", "Y+")("X+94, Y+34").unwrap().1,
+            (94, 34)
+        );
+        assert_eq!(
+            blocks(lines_of(parse_unsigned::<u8>))("1\n2\n\n3")
+                .unwrap()
+                .1,
+            vec![vec![1, 2], vec![3]]
+        );
+    }
+
+    #[test]
+    fn test_parse_all() {
+        assert_eq!(
+            parse_all(comma_separated::<u8>, "1,2,3\n"),
+            Ok(vec![1, 2, 3])
+        );
+
+        let err = parse_all(lines_of(comma_separated::<u8>), "1,2\n3,x").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 2));
+        assert_eq!(err.to_string().lines().last(), Some("   ^"));
+    }
 }
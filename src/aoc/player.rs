@@ -0,0 +1,289 @@
+use std::{
+    io::{self, Write},
+    time::Duration,
+};
+
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    execute, queue,
+    style::Print,
+    terminal::{self, ClearType},
+};
+
+// Every state a simulation has been through, so we can step backwards and jump around
+pub struct Timeline<S, F>
+where
+    S: Clone,
+    F: FnMut(&mut S) -> bool,
+{
+    history: Vec<S>,
+    current: usize,
+    step: F,
+    finished: bool,
+}
+
+impl<S, F> Timeline<S, F>
+where
+    S: Clone,
+    F: FnMut(&mut S) -> bool,
+{
+    pub fn new(initial: S, step: F) -> Self {
+        Self {
+            history: vec![initial],
+            current: 0,
+            step,
+            finished: false,
+        }
+    }
+
+    pub fn state(&self) -> &S {
+        &self.history[self.current]
+    }
+
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    // Only true once the step function has reported the end and we're looking at the last state
+    pub fn is_finished(&self) -> bool {
+        self.finished && self.current == self.history.len() - 1
+    }
+
+    pub fn forward(&mut self) -> bool {
+        if self.current + 1 < self.history.len() {
+            self.current += 1;
+            return true;
+        }
+        if self.finished {
+            return false;
+        }
+
+        let mut next = self.history[self.current].clone();
+        if (self.step)(&mut next) {
+            self.history.push(next);
+            self.current += 1;
+            true
+        } else {
+            self.finished = true;
+            false
+        }
+    }
+
+    pub fn back(&mut self) -> bool {
+        if self.current == 0 {
+            false
+        } else {
+            self.current -= 1;
+            true
+        }
+    }
+
+    pub fn jump(&mut self, step: usize) {
+        if step < self.history.len() {
+            self.current = step;
+            return;
+        }
+        self.current = self.history.len() - 1;
+        while self.current < step && self.forward() {}
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Playing,
+    Paused,
+    Jumping(usize),
+}
+
+pub struct Player<S, F, R>
+where
+    S: Clone,
+    F: FnMut(&mut S) -> bool,
+    R: Fn(&S) -> String,
+{
+    timeline: Timeline<S, F>,
+    render: R,
+    delay: Duration,
+    mode: Mode,
+}
+
+impl<S, F, R> Player<S, F, R>
+where
+    S: Clone,
+    F: FnMut(&mut S) -> bool,
+    R: Fn(&S) -> String,
+{
+    const MIN_DELAY: Duration = Duration::from_millis(1);
+    const MAX_DELAY: Duration = Duration::from_secs(5);
+
+    pub fn new(initial: S, step: F, render: R) -> Self {
+        Self {
+            timeline: Timeline::new(initial, step),
+            render,
+            delay: Duration::from_millis(100),
+            mode: Mode::Paused,
+        }
+    }
+
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = delay.clamp(Self::MIN_DELAY, Self::MAX_DELAY);
+        self
+    }
+
+    pub fn autoplay(mut self) -> Self {
+        self.mode = Mode::Playing;
+        self
+    }
+
+    // Runs until the user quits, handing back the state that was on screen
+    pub fn play(mut self) -> io::Result<S> {
+        let mut stdout = io::stdout();
+        let _guard = TerminalGuard::enter(&mut stdout)?;
+
+        loop {
+            self.draw(&mut stdout)?;
+
+            // Block on input unless there's a next frame to show
+            let ready = match self.mode {
+                Mode::Playing if !self.timeline.is_finished() => event::poll(self.delay)?,
+                _ => true,
+            };
+
+            if ready {
+                if let Event::Key(key) = event::read()? {
+                    if key.kind == KeyEventKind::Press && !self.handle_key(key) {
+                        break;
+                    }
+                }
+            } else if !self.timeline.forward() {
+                self.mode = Mode::Paused;
+            }
+        }
+
+        Ok(self.timeline.state().clone())
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> bool {
+        if let Mode::Jumping(target) = self.mode {
+            match key.code {
+                KeyCode::Char(c @ '0'..='9') => {
+                    let digit = c.to_digit(10).unwrap() as usize;
+                    self.mode = Mode::Jumping(target.saturating_mul(10).saturating_add(digit));
+                }
+                KeyCode::Backspace => self.mode = Mode::Jumping(target / 10),
+                KeyCode::Enter => {
+                    self.timeline.jump(target);
+                    self.mode = Mode::Paused;
+                }
+                KeyCode::Esc => self.mode = Mode::Paused,
+                _ => {}
+            }
+            return true;
+        }
+
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return false,
+            KeyCode::Char(' ') => {
+                self.mode = match self.mode {
+                    Mode::Playing => Mode::Paused,
+                    _ => Mode::Playing,
+                }
+            }
+            KeyCode::Right | KeyCode::Char('n') | KeyCode::Char('.') => {
+                self.mode = Mode::Paused;
+                self.timeline.forward();
+            }
+            KeyCode::Left | KeyCode::Char('p') | KeyCode::Char(',') => {
+                self.mode = Mode::Paused;
+                self.timeline.back();
+            }
+            KeyCode::Home => {
+                self.mode = Mode::Paused;
+                self.timeline.jump(0);
+            }
+            KeyCode::Up | KeyCode::Char('+') | KeyCode::Char('=') => {
+                self.delay = (self.delay / 2).max(Self::MIN_DELAY);
+            }
+            KeyCode::Down | KeyCode::Char('-') => {
+                self.delay = (self.delay * 2).min(Self::MAX_DELAY);
+            }
+            KeyCode::Char('g') => self.mode = Mode::Jumping(0),
+            _ => {}
+        }
+        true
+    }
+
+    fn status(&self) -> String {
+        let mode = match self.mode {
+            Mode::Playing => "playing".to_owned(),
+            Mode::Paused if self.timeline.is_finished() => "finished".to_owned(),
+            Mode::Paused => "paused".to_owned(),
+            Mode::Jumping(target) => format!("jump to: {target}_"),
+        };
+        format!(
+            "step {} | {} | {}ms/step | space play/pause, \u{2190}/\u{2192} step, +/- speed, g jump, q quit",
+            self.timeline.current(),
+            mode,
+            self.delay.as_millis()
+        )
+    }
+
+    fn draw(&self, out: &mut impl Write) -> io::Result<()> {
+        let frame = (self.render)(self.timeline.state());
+        queue!(out, cursor::MoveTo(0, 0), terminal::Clear(ClearType::All))?;
+        // Raw mode means \n no longer returns the carriage
+        for line in frame.lines() {
+            queue!(out, Print(line), Print("\r\n"))?;
+        }
+        queue!(out, Print("\r\n"), Print(self.status()))?;
+        out.flush()
+    }
+}
+
+struct TerminalGuard;
+
+impl TerminalGuard {
+    fn enter(out: &mut impl Write) -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        execute!(out, terminal::EnterAlternateScreen, cursor::Hide)?;
+        Ok(Self)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), cursor::Show, terminal::LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_timeline() {
+        let mut timeline = Timeline::new(0, |n: &mut usize| {
+            *n += 1;
+            *n <= 5
+        });
+
+        assert!(timeline.forward());
+        assert!(timeline.forward());
+        assert_eq!(*timeline.state(), 2);
+
+        assert!(timeline.back());
+        assert_eq!(*timeline.state(), 1);
+
+        timeline.jump(100);
+        assert_eq!(*timeline.state(), 5);
+        assert!(timeline.is_finished());
+        assert!(!timeline.forward());
+
+        timeline.jump(3);
+        assert_eq!(timeline.current(), 3);
+        assert!(!timeline.is_finished());
+    }
+}
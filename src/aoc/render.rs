@@ -0,0 +1,431 @@
+use std::{
+    fs, io,
+    ops::Index,
+    path::{Path, PathBuf},
+};
+
+use super::grid::{Grid, Point};
+
+pub type Rgb = [u8; 3];
+
+pub const BLACK: Rgb = [0, 0, 0];
+pub const WHITE: Rgb = [255, 255, 255];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<Rgb>,
+}
+
+impl Image {
+    pub fn new(width: usize, height: usize, background: Rgb) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![background; width * height],
+        }
+    }
+
+    pub fn from_grid<T, F>(grid: &Grid<T>, palette: F) -> Self
+    where
+        T: Index<usize>,
+        F: Fn(&T::Output) -> Rgb,
+    {
+        Self {
+            width: grid.width(),
+            height: grid.height(),
+            pixels: grid.iter().map(|cell| palette(&cell.value())).collect(),
+        }
+    }
+
+    pub fn from_points<I>(width: usize, height: usize, points: I, fg: Rgb, bg: Rgb) -> Self
+    where
+        I: IntoIterator<Item = Point>,
+    {
+        let mut image = Self::new(width, height, bg);
+        for point in points {
+            image.set(&point, fg);
+        }
+        image
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, point: &Point) -> Option<Rgb> {
+        (point.x < self.width && point.y < self.height)
+            .then(|| self.pixels[point.y * self.width + point.x])
+    }
+
+    // Points outside the image are ignored
+    pub fn set(&mut self, point: &Point, colour: Rgb) {
+        if point.x < self.width && point.y < self.height {
+            self.pixels[point.y * self.width + point.x] = colour;
+        }
+    }
+
+    pub fn scale(&self, factor: usize) -> Self {
+        let width = self.width * factor;
+        let height = self.height * factor;
+        let pixels = (0..width * height)
+            .map(|i| {
+                let (x, y) = (i % width / factor, i / width / factor);
+                self.pixels[y * self.width + x]
+            })
+            .collect();
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    pub fn to_ppm(&self) -> Vec<u8> {
+        let mut out = format!("P6\n{} {}\n255\n", self.width, self.height).into_bytes();
+        out.extend(self.pixels.iter().flatten());
+        out
+    }
+
+    pub fn to_png(&self) -> Vec<u8> {
+        let mut out = PNG_SIGNATURE.to_vec();
+        write_chunk(&mut out, b"IHDR", &self.ihdr());
+        write_chunk(&mut out, b"IDAT", &zlib_stored(&self.scanlines()));
+        write_chunk(&mut out, b"IEND", &[]);
+        out
+    }
+
+    pub fn write(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        let bytes = match Format::from_path(path)? {
+            Format::Ppm => self.to_ppm(),
+            Format::Png => self.to_png(),
+        };
+        fs::write(path, bytes)
+    }
+
+    fn ihdr(&self) -> Vec<u8> {
+        let mut ihdr = Vec::with_capacity(13);
+        ihdr.extend((self.width as u32).to_be_bytes());
+        ihdr.extend((self.height as u32).to_be_bytes());
+        // 8 bit depth, truecolour, deflate, no filter, no interlace
+        ihdr.extend([8, 2, 0, 0, 0]);
+        ihdr
+    }
+
+    fn scanlines(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(self.height * (1 + self.width * 3));
+        for row in self.pixels.chunks(self.width.max(1)) {
+            out.push(0);
+            out.extend(row.iter().flatten());
+        }
+        out
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Ppm,
+    Png,
+}
+
+impl Format {
+    pub fn from_path(path: &Path) -> io::Result<Self> {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("ppm") => Ok(Self::Ppm),
+            Some("png") => Ok(Self::Png),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Unsupported image format: {}", path.display()),
+            )),
+        }
+    }
+
+    pub const fn extension(&self) -> &'static str {
+        match self {
+            Self::Ppm => "ppm",
+            Self::Png => "png",
+        }
+    }
+}
+
+// Writes numbered frames into a directory as a simulation runs
+pub struct FrameWriter {
+    dir: PathBuf,
+    format: Format,
+    frame: usize,
+}
+
+impl FrameWriter {
+    pub fn new(dir: impl Into<PathBuf>, format: Format) -> io::Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        Ok(Self {
+            dir,
+            format,
+            frame: 0,
+        })
+    }
+
+    pub fn frame(&self) -> usize {
+        self.frame
+    }
+
+    pub fn path(&self, frame: usize) -> PathBuf {
+        self.dir
+            .join(format!("{frame:06}.{}", self.format.extension()))
+    }
+
+    pub fn write(&mut self, image: &Image) -> io::Result<PathBuf> {
+        let path = self.path(self.frame);
+        image.write(&path)?;
+        self.frame += 1;
+        Ok(path)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Animation {
+    frames: Vec<Image>,
+    delay_ms: u16,
+    loops: u32,
+}
+
+impl Animation {
+    pub fn new(delay_ms: u16) -> Self {
+        Self {
+            frames: vec![],
+            delay_ms,
+            loops: 0,
+        }
+    }
+
+    pub fn loops(mut self, loops: u32) -> Self {
+        self.loops = loops;
+        self
+    }
+
+    pub fn push(&mut self, frame: Image) {
+        if let Some(first) = self.frames.first() {
+            assert!(
+                first.width == frame.width && first.height == frame.height,
+                "Animation frames must all be the same size"
+            );
+        }
+        self.frames.push(frame);
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    pub fn frames(&self) -> &[Image] {
+        &self.frames
+    }
+
+    pub fn to_apng(&self) -> Vec<u8> {
+        let Some(first) = self.frames.first() else {
+            return vec![];
+        };
+
+        let mut out = PNG_SIGNATURE.to_vec();
+        write_chunk(&mut out, b"IHDR", &first.ihdr());
+
+        let mut actl = Vec::with_capacity(8);
+        actl.extend((self.frames.len() as u32).to_be_bytes());
+        actl.extend(self.loops.to_be_bytes());
+        write_chunk(&mut out, b"acTL", &actl);
+
+        let mut sequence = 0u32;
+        for (i, frame) in self.frames.iter().enumerate() {
+            write_chunk(&mut out, b"fcTL", &self.fctl(sequence, frame));
+            sequence += 1;
+
+            let data = zlib_stored(&frame.scanlines());
+            if i == 0 {
+                write_chunk(&mut out, b"IDAT", &data);
+            } else {
+                let mut fdat = Vec::with_capacity(4 + data.len());
+                fdat.extend(sequence.to_be_bytes());
+                fdat.extend(data);
+                write_chunk(&mut out, b"fdAT", &fdat);
+                sequence += 1;
+            }
+        }
+
+        write_chunk(&mut out, b"IEND", &[]);
+        out
+    }
+
+    pub fn write_apng(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.to_apng())
+    }
+
+    fn fctl(&self, sequence: u32, frame: &Image) -> Vec<u8> {
+        let mut fctl = Vec::with_capacity(26);
+        fctl.extend(sequence.to_be_bytes());
+        fctl.extend((frame.width as u32).to_be_bytes());
+        fctl.extend((frame.height as u32).to_be_bytes());
+        // x and y offsets
+        fctl.extend([0; 8]);
+        fctl.extend(self.delay_ms.to_be_bytes());
+        fctl.extend(1000u16.to_be_bytes());
+        // No disposal, overwrite the previous frame
+        fctl.extend([0, 0]);
+        fctl
+    }
+}
+
+const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+
+fn write_chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    out.extend((data.len() as u32).to_be_bytes());
+    let start = out.len();
+    out.extend(kind);
+    out.extend(data);
+    let crc = crc32(&out[start..]);
+    out.extend(crc.to_be_bytes());
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(!0u32, |crc, &b| {
+        (0..8).fold(crc ^ b as u32, |crc, _| {
+            if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb88320
+            } else {
+                crc >> 1
+            }
+        })
+    })
+}
+
+fn adler32(bytes: &[u8]) -> u32 {
+    let (a, b) = bytes.iter().fold((1u32, 0u32), |(a, b), &byte| {
+        let a = (a + byte as u32) % 65521;
+        (a, (b + a) % 65521)
+    });
+    (b << 16) | a
+}
+
+// Uncompressed deflate blocks, frames are small enough that compressing isn't worth the code
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    const MAX_BLOCK: usize = u16::MAX as usize;
+
+    let mut out = vec![0x78, 0x01];
+    let mut blocks = data.chunks(MAX_BLOCK).peekable();
+    if blocks.peek().is_none() {
+        out.extend([1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        let last = blocks.peek().is_none();
+        let len = block.len() as u16;
+        out.push(last as u8);
+        out.extend(len.to_le_bytes());
+        out.extend((!len).to_le_bytes());
+        out.extend(block);
+    }
+    out.extend(adler32(data).to_be_bytes());
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_checksums() {
+        assert_eq!(crc32(b"IEND"), 0xae426082);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e60398);
+    }
+
+    #[test]
+    fn test_encode() {
+        let grid: Grid<Vec<char>> = "#.\n.#".parse().unwrap();
+        let image = Image::from_grid(&grid, |&c| if c == '#' { WHITE } else { BLACK });
+
+        assert_eq!(
+            image.to_ppm(),
+            b"P6\n2 2\n255\n\xff\xff\xff\0\0\0\0\0\0\xff\xff\xff".to_vec()
+        );
+
+        let png = image.scale(2).to_png();
+        assert_eq!(&png[..8], &PNG_SIGNATURE);
+        assert_eq!(&png[12..16], b"IHDR");
+        assert_eq!(&png[16..24], &[0, 0, 0, 4, 0, 0, 0, 4]);
+        assert_eq!(&png[png.len() - 8..png.len() - 4], b"IEND");
+    }
+
+    // (kind, data) for each chunk, checking every CRC on the way
+    fn chunks(png: &[u8]) -> Vec<([u8; 4], &[u8])> {
+        assert_eq!(&png[..8], &PNG_SIGNATURE);
+        let mut out = vec![];
+        let mut rest = &png[8..];
+        while !rest.is_empty() {
+            let len = u32::from_be_bytes(rest[..4].try_into().unwrap()) as usize;
+            let (body, crc) = rest[4..].split_at(4 + len);
+            assert_eq!(
+                crc32(body),
+                u32::from_be_bytes(crc[..4].try_into().unwrap())
+            );
+            out.push((body[..4].try_into().unwrap(), &body[4..]));
+            rest = &crc[4..];
+        }
+        out
+    }
+
+    #[test]
+    fn test_apng() {
+        let mut animation = Animation::new(50).loops(2);
+        for n in 0..3 {
+            let mut image = Image::new(2, 1, BLACK);
+            image.set(&Point::new(n % 2, 0), WHITE);
+            animation.push(image);
+        }
+        let apng = animation.to_apng();
+        let chunks = chunks(&apng);
+
+        let kinds: Vec<&[u8]> = chunks.iter().map(|(kind, _)| &kind[..]).collect();
+        assert_eq!(
+            kinds,
+            [b"IHDR", b"acTL", b"fcTL", b"IDAT", b"fcTL", b"fdAT", b"fcTL", b"fdAT", b"IEND"]
+        );
+        // 3 frames, played twice
+        assert_eq!(chunks[1].1, [0, 0, 0, 3, 0, 0, 0, 2]);
+
+        let sequence: Vec<u32> = chunks
+            .iter()
+            .filter(|(kind, _)| kind == b"fcTL" || kind == b"fdAT")
+            .map(|(_, data)| u32::from_be_bytes(data[..4].try_into().unwrap()))
+            .collect();
+        assert_eq!(sequence, [0, 1, 2, 3, 4]);
+
+        assert_eq!(
+            chunks[5].1[4..],
+            zlib_stored(&animation.frames()[1].scanlines())
+        );
+        assert!(Animation::new(50).to_apng().is_empty());
+    }
+
+    #[test]
+    fn test_frame_writer() {
+        let dir = std::env::temp_dir().join(format!("aoc_frames_{}", std::process::id()));
+        let mut frames = FrameWriter::new(&dir, Format::Ppm).unwrap();
+        let image = Image::new(1, 1, WHITE);
+
+        assert_eq!(frames.write(&image).unwrap(), dir.join("000000.ppm"));
+        assert_eq!(frames.write(&image).unwrap(), dir.join("000001.ppm"));
+        assert_eq!(frames.frame(), 2);
+        assert_eq!(fs::read(frames.path(1)).unwrap(), image.to_ppm());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
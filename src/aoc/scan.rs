@@ -0,0 +1,261 @@
+use std::fmt::Display;
+
+use crate::point2d::Point2D;
+
+// Column is 1-based, counted in chars from the start of the scanned text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScanError {
+    pub column: usize,
+    pub message: String,
+}
+
+impl Display for ScanError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Scan error at column {}: {}", self.column, self.message)
+    }
+}
+
+impl std::error::Error for ScanError {}
+
+// Anything that can fill a {} in a template
+pub trait Scan: Sized {
+    fn scan(s: &str) -> Result<Self, String>;
+}
+
+macro_rules! impl_scan_from_str {
+    ($($typ:ty),*) => {
+        $(
+            impl Scan for $typ {
+                fn scan(s: &str) -> Result<Self, String> {
+                    s.trim()
+                        .parse()
+                        .map_err(|e| format!("{s:?} is not a valid {}: {e}", stringify!($typ)))
+                }
+            }
+        )*
+    };
+}
+impl_scan_from_str!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+impl_scan_from_str!(f32, f64, bool, char);
+
+impl Scan for String {
+    fn scan(s: &str) -> Result<Self, String> {
+        Ok(s.to_string())
+    }
+}
+
+// Comma separated if there are any commas, otherwise whitespace separated
+impl<T: Scan> Scan for Vec<T> {
+    fn scan(s: &str) -> Result<Self, String> {
+        if s.contains(',') {
+            s.split(',').map(|part| T::scan(part.trim())).collect()
+        } else {
+            s.split_whitespace().map(T::scan).collect()
+        }
+    }
+}
+
+impl<T: Scan> Scan for Option<T> {
+    fn scan(s: &str) -> Result<Self, String> {
+        if s.trim().is_empty() {
+            Ok(None)
+        } else {
+            T::scan(s).map(Some)
+        }
+    }
+}
+
+impl<N: Scan> Scan for Point2D<N> {
+    fn scan(s: &str) -> Result<Self, String> {
+        let (x, y) = s
+            .split_once(',')
+            .ok_or_else(|| format!("{s:?} is not an x,y pair"))?;
+        Ok(Point2D::new(N::scan(x)?, N::scan(y)?))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Piece<'a> {
+    Literal(&'a str),
+    Hole,
+}
+
+fn pieces(template: &str) -> Vec<Piece<'_>> {
+    let mut out = vec![];
+    let mut rest = template;
+    while let Some(i) = rest.find("{}") {
+        if i > 0 {
+            out.push(Piece::Literal(&rest[..i]));
+        }
+        assert!(
+            !(i == 0 && out.last() == Some(&Piece::Hole)),
+            "Template {template:?} has two {{}} with nothing between them"
+        );
+        out.push(Piece::Hole);
+        rest = &rest[i + 2..];
+    }
+    if !rest.is_empty() {
+        out.push(Piece::Literal(rest));
+    }
+    out
+}
+
+fn error(input: &str, offset: usize, message: String) -> ScanError {
+    ScanError {
+        column: input[..offset].chars().count() + 1,
+        message,
+    }
+}
+
+// Number of {} in a template, const so that scan! can check it against its types while
+// compiling
+pub const fn placeholder_count(template: &str) -> usize {
+    let bytes = template.as_bytes();
+    let (mut i, mut count) = (0, 0);
+    while i + 1 < bytes.len() {
+        if bytes[i] == b'{' && bytes[i + 1] == b'}' {
+            count += 1;
+            i += 2;
+        } else {
+            i += 1;
+        }
+    }
+    count
+}
+
+// The text matched by each {} in the template, along with its byte offset into input.
+// A {} takes everything up to the next literal, or the rest of the input if it's last.
+pub fn split_template<'a>(
+    template: &str,
+    input: &'a str,
+) -> Result<Vec<(usize, &'a str)>, ScanError> {
+    let pieces = pieces(template);
+    let mut out = vec![];
+    let mut pos = 0;
+
+    for (i, piece) in pieces.iter().enumerate() {
+        match piece {
+            Piece::Literal(literal) => {
+                if !input[pos..].starts_with(literal) {
+                    return Err(error(input, pos, format!("expected {literal:?}")));
+                }
+                pos += literal.len();
+            }
+            Piece::Hole => {
+                let end = match pieces.get(i + 1) {
+                    Some(Piece::Literal(literal)) => {
+                        pos + input[pos..].find(literal).ok_or_else(|| {
+                            error(input, pos, format!("expected {literal:?} after this"))
+                        })?
+                    }
+                    _ => input.len(),
+                };
+                out.push((pos, &input[pos..end]));
+                pos = end;
+            }
+        }
+    }
+
+    if pos < input.len() {
+        return Err(error(
+            input,
+            pos,
+            format!("unexpected {:?} at the end", &input[pos..]),
+        ));
+    }
+    Ok(out)
+}
+
+// Whether two {} follow each other with nothing between them, which leaves no way to tell
+// where the first one ends. Const for the same reason as placeholder_count.
+pub const fn adjacent_placeholders(template: &str) -> bool {
+    let bytes = template.as_bytes();
+    let (mut i, mut after_placeholder) = (0, usize::MAX);
+    while i + 1 < bytes.len() {
+        if bytes[i] == b'{' && bytes[i + 1] == b'}' {
+            if i == after_placeholder {
+                return true;
+            }
+            i += 2;
+            after_placeholder = i;
+        } else {
+            i += 1;
+        }
+    }
+    false
+}
+
+// Scans one piece from split_template, with errors pointing into the original input
+pub fn scan_field<T: Scan>(input: &str, (offset, text): (usize, &str)) -> Result<T, ScanError> {
+    T::scan(text).map_err(|message| error(input, offset, message))
+}
+
+// scan!(line, "{}: {}", usize, Vec<usize>) gives a Result of a tuple with one value per {}
+#[macro_export]
+macro_rules! scan {
+    ($input:expr, $template:literal, $($typ:ty),+ $(,)?) => {{
+        const _: () = {
+            assert!(
+                $crate::aoc::scan::placeholder_count($template) == [$(stringify!($typ)),+].len(),
+                "scan! needs as many types as its template has placeholders"
+            );
+            assert!(
+                !$crate::aoc::scan::adjacent_placeholders($template),
+                "scan! template has two placeholders with nothing between them"
+            );
+        };
+        let input: &str = $input;
+        (|| -> Result<_, $crate::aoc::scan::ScanError> {
+            let mut parts = $crate::aoc::scan::split_template($template, input)?.into_iter();
+            Ok(($($crate::aoc::scan::scan_field::<$typ>(input, parts.next().unwrap())?,)+))
+        })()
+    }};
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_split_template() {
+        assert_eq!(
+            split_template("{}: {}", "190: 10 19"),
+            Ok(vec![(0, "190"), (5, "10 19")])
+        );
+        assert_eq!(
+            split_template("p={} v={}", "p=0,4 v=3,-3"),
+            Ok(vec![(2, "0,4"), (8, "3,-3")])
+        );
+        assert_eq!(split_template("{}|{}", "47-53").unwrap_err().column, 1);
+        assert_eq!(split_template("{}!", "a!b").unwrap_err().column, 3);
+
+        assert_eq!(placeholder_count("{}: {}"), 2);
+        assert_eq!(placeholder_count("{{}}{"), 1);
+        assert_eq!(placeholder_count(""), 0);
+        assert!(adjacent_placeholders("{}{}"));
+        assert!(adjacent_placeholders("a {{}{} b"));
+        assert!(!adjacent_placeholders("{} {}"));
+        assert!(!adjacent_placeholders("{{}}"));
+    }
+
+    #[test]
+    fn test_scan() {
+        let (result, args) = crate::scan!("190: 10 19", "{}: {}", usize, Vec<usize>).unwrap();
+        assert_eq!((result, args), (190, vec![10, 19]));
+
+        let (p, v) = crate::scan!("p=0,4 v=3,-3", "p={} v={}", Point2D<i32>, Point2D<i32>).unwrap();
+        assert_eq!((p, v), (Point2D::new(0, 4), Point2D::new(3, -3)));
+
+        let err = crate::scan!("Register A: x", "Register {}: {}", char, u64).unwrap_err();
+        assert_eq!(err.column, 13);
+    }
+
+    #[test]
+    fn test_derive() {
+        #[derive(Debug, PartialEq, aoc_macros::AocParse)]
+        #[aoc(format = "{}: {}")]
+        struct Equation(u64, Vec<u64>);
+
+        assert_eq!("190: 10 19".parse(), Ok(Equation(190, vec![10, 19])));
+    }
+}
@@ -0,0 +1,592 @@
+use std::{
+    collections::BTreeSet,
+    fmt::{Display, Write},
+};
+
+use super::parse::{parse_all, ParseError};
+
+pub mod asm;
+pub mod machine;
+pub mod quine;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OpCode(pub u8);
+
+#[derive(Debug, Clone)]
+pub enum OpcodeParseError<T> {
+    OutOfRange(T),
+}
+
+impl OpCode {
+    pub fn from_u8(n: u8) -> Result<Self, OpcodeParseError<u8>> {
+        match n {
+            0..=7 => Ok(Self(n)),
+            _ => Err(OpcodeParseError::OutOfRange(n)),
+        }
+    }
+
+    pub fn from_int<T>(n: T) -> Result<Self, OpcodeParseError<T>>
+    where
+        T: Copy + TryInto<u8>,
+    {
+        let as_u8: u8 = n.try_into().map_err(|_| OpcodeParseError::OutOfRange(n))?;
+        Self::from_u8(as_u8).map_err(|_| OpcodeParseError::OutOfRange(n))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VmError {
+    // An instruction at ip whose operand is past the end of the program
+    MissingOperand { ip: usize },
+    // Combo operand 7 is reserved
+    ReservedOperand { ip: usize },
+    StepLimit { steps: usize },
+    // A dv instruction at ip shifting by a negative amount
+    NegativeShift { ip: usize, shift: isize },
+    // A machine running ThreeBit that doesn't have exactly registers a, b and c
+    RegisterCount { registers: usize },
+}
+
+impl Display for VmError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VmError::MissingOperand { ip } => {
+                write!(f, "Instruction at {ip} has no operand")
+            }
+            VmError::ReservedOperand { ip } => {
+                write!(f, "Instruction at {ip} uses reserved combo operand 7")
+            }
+            VmError::StepLimit { steps } => write!(f, "Stopped after {steps} steps"),
+            VmError::NegativeShift { ip, shift } => {
+                write!(f, "Instruction at {ip} shifts by {shift}")
+            }
+            VmError::RegisterCount { registers } => {
+                write!(
+                    f,
+                    "Needs registers a, b and c but has {registers} registers"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for VmError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Operand(pub OpCode);
+
+impl Operand {
+    pub fn literal(&self) -> isize {
+        self.0 .0 as isize
+    }
+
+    // None for the reserved operand 7
+    pub fn combo(&self) -> Option<ComboOperand> {
+        self.0.try_into().ok()
+    }
+
+    pub fn combo_value(&self, computer: &Computer) -> Option<isize> {
+        self.combo().map(|combo| combo.value(computer.registers()))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ComboOperand {
+    Literal(u8),
+    A,
+    B,
+    C,
+}
+
+impl ComboOperand {
+    fn value(&self, [a, b, c]: [isize; 3]) -> isize {
+        match self {
+            ComboOperand::Literal(n) => (*n).into(),
+            ComboOperand::A => a,
+            ComboOperand::B => b,
+            ComboOperand::C => c,
+        }
+    }
+}
+
+impl TryFrom<OpCode> for ComboOperand {
+    type Error = OpCode;
+
+    fn try_from(op @ OpCode(n): OpCode) -> Result<Self, Self::Error> {
+        match n {
+            0..=3 => Ok(Self::Literal(n)),
+            4 => Ok(Self::A),
+            5 => Ok(Self::B),
+            6 => Ok(Self::C),
+            _ => Err(op),
+        }
+    }
+}
+
+impl Display for ComboOperand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ComboOperand::Literal(n) => write!(f, "{n}"),
+            ComboOperand::A => f.write_char('a'),
+            ComboOperand::B => f.write_char('b'),
+            ComboOperand::C => f.write_char('c'),
+        }
+    }
+}
+
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instruction {
+    // a = a >> combo
+    ADV,
+    // b = b ^ literal
+    BXL,
+    // b = combo & 7
+    BST,
+    // Jumps to literal unless a is 0
+    JNZ,
+    // b = b ^ c, ignoring its operand
+    BXC,
+    // Outputs combo & 7
+    OUT,
+    // b = a >> combo
+    BDV,
+    // c = a >> combo
+    CDV,
+}
+
+impl Instruction {
+    pub const ALL: [Instruction; 8] = [
+        Self::ADV,
+        Self::BXL,
+        Self::BST,
+        Self::JNZ,
+        Self::BXC,
+        Self::OUT,
+        Self::BDV,
+        Self::CDV,
+    ];
+
+    pub fn opcode(&self) -> OpCode {
+        OpCode(*self as u8)
+    }
+
+    pub fn mnemonic(&self) -> &'static str {
+        match self {
+            Self::ADV => "adv",
+            Self::BXL => "bxl",
+            Self::BST => "bst",
+            Self::JNZ => "jnz",
+            Self::BXC => "bxc",
+            Self::OUT => "out",
+            Self::BDV => "bdv",
+            Self::CDV => "cdv",
+        }
+    }
+
+    pub fn takes_combo(&self) -> bool {
+        !matches!(self, Self::BXL | Self::JNZ | Self::BXC)
+    }
+}
+
+impl Display for Instruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl From<OpCode> for Instruction {
+    fn from(OpCode(n): OpCode) -> Self {
+        Self::ALL[n as usize]
+    }
+}
+
+// "bst a", "bxl 5", with bxc's ignored operand left off when it's 0
+pub fn disassemble_one(instruction: Instruction, operand: Operand) -> String {
+    let mnemonic = instruction.mnemonic();
+    match instruction {
+        Instruction::BXC if operand.literal() == 0 => mnemonic.to_string(),
+        _ if instruction.takes_combo() => match operand.combo() {
+            Some(combo) => format!("{mnemonic} {combo}"),
+            None => format!("{mnemonic} ?{}", operand.literal()),
+        },
+        _ => format!("{mnemonic} {}", operand.literal()),
+    }
+}
+
+// One instruction per line, which asm::assemble turns back into the same program
+pub fn disassemble(program: &[OpCode]) -> Result<String, VmError> {
+    let mut out = String::new();
+    for ip in (0..program.len()).step_by(2) {
+        let operand = program.get(ip + 1).ok_or(VmError::MissingOperand { ip })?;
+        writeln!(
+            out,
+            "{}",
+            disassemble_one(program[ip].into(), Operand(*operand))
+        )
+        .unwrap();
+    }
+    Ok(out)
+}
+
+// a >> shift, as the dv instructions do it. Shifting past the width of a leaves only its sign,
+// which is what dividing by 2^shift and rounding down would give.
+fn dv(a: isize, shift: isize, ip: usize) -> Result<isize, VmError> {
+    if shift < 0 {
+        return Err(VmError::NegativeShift { ip, shift });
+    }
+    Ok(u32::try_from(shift)
+        .ok()
+        .and_then(|shift| a.checked_shr(shift))
+        .unwrap_or(a >> (isize::BITS - 1)))
+}
+
+// What an instruction does besides changing the registers
+enum Outcome {
+    Next,
+    Jump(usize),
+    Output(u8),
+}
+
+// Runs one instruction against registers a, b and c. Computer and machine::ThreeBit both
+// go through this, so they can't disagree. Inlined, as a call per instruction costs
+// Computer about 70% on day 17.
+#[inline]
+fn execute(
+    registers: &mut [isize; 3],
+    instruction: Instruction,
+    operand: Operand,
+    ip: usize,
+) -> Result<Outcome, VmError> {
+    let [a, b, c] = *registers;
+    let combo = || {
+        operand
+            .combo()
+            .map(|combo| combo.value([a, b, c]))
+            .ok_or(VmError::ReservedOperand { ip })
+    };
+
+    match instruction {
+        Instruction::ADV => registers[0] = dv(a, combo()?, ip)?,
+        Instruction::BXL => registers[1] = b ^ operand.literal(),
+        Instruction::BST => registers[1] = combo()? & 7,
+        Instruction::JNZ if a != 0 => return Ok(Outcome::Jump(operand.literal() as usize)),
+        Instruction::JNZ => {}
+        Instruction::BXC => registers[1] = b ^ c,
+        Instruction::OUT => return Ok(Outcome::Output((combo()? & 7) as u8)),
+        Instruction::BDV => registers[1] = dv(a, combo()?, ip)?,
+        Instruction::CDV => registers[2] = dv(a, combo()?, ip)?,
+    }
+    Ok(Outcome::Next)
+}
+
+pub enum InstructionResult {
+    Output(u8),
+    Nothing,
+    Halt,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stop {
+    Halted,
+    Breakpoint(usize),
+}
+
+// What run_traced reports after each instruction, registers are after it ran
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Trace {
+    pub step: usize,
+    pub ip: usize,
+    pub instruction: Instruction,
+    pub operand: Operand,
+    pub registers: [isize; 3],
+    pub output: Option<u8>,
+}
+
+impl Display for Trace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let [a, b, c] = self.registers;
+        write!(
+            f,
+            "{:>6} {:>3}: {:<8} a={a} b={b} c={c}",
+            self.step,
+            self.ip,
+            disassemble_one(self.instruction, self.operand)
+        )?;
+        if let Some(n) = self.output {
+            write!(f, " out {n}")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct Computer {
+    pub a: isize,
+    pub b: isize,
+    pub c: isize,
+
+    pub program: Vec<OpCode>,
+    pub ip: usize,
+
+    pub steps: usize,
+    pub step_limit: Option<usize>,
+    pub breakpoints: BTreeSet<usize>,
+}
+
+impl Computer {
+    pub fn new(a: isize, b: isize, c: isize, program: Vec<OpCode>) -> Self {
+        Self {
+            a,
+            b,
+            c,
+            program,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_step_limit(mut self, limit: usize) -> Self {
+        self.step_limit = Some(limit);
+        self
+    }
+
+    pub fn registers(&self) -> [isize; 3] {
+        [self.a, self.b, self.c]
+    }
+
+    // None once ip is past the end, which halts
+    pub fn read(&self) -> Result<Option<(Instruction, Operand)>, VmError> {
+        let Some(&opcode) = self.program.get(self.ip) else {
+            return Ok(None);
+        };
+        let operand = self
+            .program
+            .get(self.ip + 1)
+            .ok_or(VmError::MissingOperand { ip: self.ip })?;
+        Ok(Some((opcode.into(), Operand(*operand))))
+    }
+
+    pub fn try_set_program<I, O>(&mut self, program: I) -> Result<(), OpcodeParseError<O>>
+    where
+        O: Copy + TryInto<u8>,
+        I: IntoIterator<Item = O>,
+    {
+        self.program = program
+            .into_iter()
+            .map(|o| OpCode::from_int(o))
+            .collect::<Result<Vec<OpCode>, _>>()?;
+        Ok(())
+    }
+
+    pub fn step(&mut self) -> Result<InstructionResult, VmError> {
+        let Some((instruction, operand)) = self.read()? else {
+            return Ok(InstructionResult::Halt);
+        };
+        if self.step_limit.is_some_and(|limit| self.steps >= limit) {
+            return Err(VmError::StepLimit { steps: self.steps });
+        }
+
+        let mut registers = self.registers();
+        let outcome = execute(&mut registers, instruction, operand, self.ip)?;
+        [self.a, self.b, self.c] = registers;
+        self.steps += 1;
+
+        match outcome {
+            Outcome::Next => self.ip += 2,
+            Outcome::Jump(ip) => self.ip = ip,
+            Outcome::Output(n) => {
+                self.ip += 2;
+                return Ok(InstructionResult::Output(n));
+            }
+        }
+        Ok(InstructionResult::Nothing)
+    }
+
+    // Runs until halting or reaching a breakpoint, calling trace after each instruction.
+    // A breakpoint at the current ip is skipped so that running again carries on past it.
+    pub fn run_traced(&mut self, mut trace: impl FnMut(&Trace)) -> Result<Stop, VmError> {
+        let mut first = true;
+
+        loop {
+            if !first && self.breakpoints.contains(&self.ip) {
+                return Ok(Stop::Breakpoint(self.ip));
+            }
+            first = false;
+
+            let Some((instruction, operand)) = self.read()? else {
+                return Ok(Stop::Halted);
+            };
+            let ip = self.ip;
+            let output = match self.step()? {
+                InstructionResult::Output(n) => Some(n),
+                InstructionResult::Nothing => None,
+                InstructionResult::Halt => return Ok(Stop::Halted),
+            };
+            trace(&Trace {
+                step: self.steps,
+                ip,
+                instruction,
+                operand,
+                registers: self.registers(),
+                output,
+            });
+        }
+    }
+
+    pub fn run(&mut self) -> Result<Stop, VmError> {
+        self.run_traced(|_| {})
+    }
+
+    // Ignores breakpoints, and ends after the first error
+    pub fn iter_output(&mut self) -> impl Iterator<Item = Result<u8, VmError>> + '_ {
+        let mut done = false;
+        std::iter::from_fn(move || {
+            while !done {
+                match self.step() {
+                    Ok(InstructionResult::Output(n)) => return Some(Ok(n)),
+                    Ok(InstructionResult::Nothing) => {}
+                    Ok(InstructionResult::Halt) => done = true,
+                    Err(e) => {
+                        done = true;
+                        return Some(Err(e));
+                    }
+                }
+            }
+            None
+        })
+    }
+
+    pub fn output(&mut self) -> Result<Vec<u8>, VmError> {
+        self.iter_output().collect()
+    }
+}
+
+impl Display for Computer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Register A: {}", self.a)?;
+        writeln!(f, "Register B: {}", self.b)?;
+        writeln!(f, "Register C: {}", self.c)?;
+
+        write!(f, "\nProgram: ")?;
+
+        for (i, &OpCode(op)) in self.program.iter().enumerate() {
+            if i != 0 {
+                f.write_char(',')?;
+            }
+            write!(f, "{}", op)?;
+        }
+        f.write_char('\n')?;
+        for _ in 0..(9 + self.ip * 2) {
+            f.write_char(' ')?;
+        }
+        f.write_char('^')
+    }
+}
+
+pub fn parse(s: &str) -> Result<Computer, ParseError> {
+    parse_all(parse::parse_computer, s)
+}
+
+mod parse {
+    use crate::aoc::parse::{labelled, parse_number, parse_unsigned};
+    use nom::{
+        character::complete::{char, line_ending},
+        combinator::map_res,
+        multi::separated_list1,
+        sequence::{preceded, terminated},
+    };
+
+    use super::{Computer, OpCode};
+
+    pub fn parse_computer(s: &str) -> nom::IResult<&str, Computer> {
+        let (s, a) = terminated(labelled("Register A", parse_unsigned), line_ending)(s)?;
+        let (s, b) = terminated(labelled("Register B", parse_unsigned), line_ending)(s)?;
+        let (s, c) = terminated(labelled("Register C", parse_unsigned), line_ending)(s)?;
+
+        let (s, program) = preceded(
+            line_ending,
+            labelled(
+                "Program",
+                separated_list1(char(','), map_res(parse_number, OpCode::from_u8)),
+            ),
+        )(s)?;
+
+        Ok((s, Computer::new(a, b, c, program)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn computer(a: isize, program: &[u8]) -> Computer {
+        let mut computer = Computer {
+            a,
+            ..Default::default()
+        };
+        computer.try_set_program(program.iter().copied()).unwrap();
+        computer
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            computer(0, &[5, 4, 2]).output(),
+            Err(VmError::MissingOperand { ip: 2 })
+        );
+        assert_eq!(
+            computer(0, &[2, 7]).run(),
+            Err(VmError::ReservedOperand { ip: 0 })
+        );
+        assert_eq!(
+            computer(1, &[3, 0]).with_step_limit(10).run(),
+            Err(VmError::StepLimit { steps: 10 })
+        );
+    }
+
+    #[test]
+    fn test_shifts() {
+        let mut big = computer(1 << 40, &[0, 5]);
+        big.b = 100;
+        assert_eq!(big.run(), Ok(Stop::Halted));
+        assert_eq!(big.a, 0);
+
+        let mut negative = computer(-8, &[6, 5, 7, 0]);
+        negative.b = isize::MAX;
+        assert_eq!(negative.run(), Ok(Stop::Halted));
+        assert_eq!((negative.b, negative.c), (-1, -8));
+
+        let mut backwards = computer(1, &[0, 6]);
+        backwards.c = -1;
+        assert_eq!(
+            backwards.run(),
+            Err(VmError::NegativeShift { ip: 0, shift: -1 })
+        );
+    }
+
+    #[test]
+    fn test_breakpoints() {
+        let mut computer = computer(2024, &[0, 1, 5, 4, 3, 0]);
+        computer.breakpoints.insert(4);
+
+        let mut outputs = vec![];
+        let mut trace = |t: &Trace| outputs.extend(t.output);
+        assert_eq!(computer.run_traced(&mut trace), Ok(Stop::Breakpoint(4)));
+        assert_eq!(computer.run_traced(&mut trace), Ok(Stop::Breakpoint(4)));
+        assert_eq!(outputs, vec![4, 2]);
+
+        computer.breakpoints.clear();
+        assert_eq!(computer.run(), Ok(Stop::Halted));
+        assert_eq!(computer.a, 0);
+    }
+
+    #[test]
+    fn test_disassemble() {
+        let program = [2, 4, 1, 5, 7, 5, 4, 3, 5, 5, 3, 0].map(OpCode);
+        assert_eq!(
+            disassemble(&program).unwrap(),
+            "bst a\nbxl 5\ncdv b\nbxc 3\nout b\njnz 0\n"
+        );
+        assert!(disassemble(&program[..3]).is_err());
+    }
+}
@@ -0,0 +1,175 @@
+use std::{collections::HashMap, fmt::Display};
+
+use super::{Instruction, OpCode};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AsmError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl Display for AsmError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Assembly error on line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for AsmError {}
+
+struct Statement<'a> {
+    line: usize,
+    mnemonic: &'a str,
+    operand: Option<&'a str>,
+}
+
+fn error<T>(line: usize, message: String) -> Result<T, AsmError> {
+    Err(AsmError { line, message })
+}
+
+fn is_label(s: &str) -> bool {
+    s.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+// Statements are split by newlines or ;, # starts a comment, and "name:" labels the next
+// statement. Gives the statements and the address of each label.
+fn statements(source: &str) -> Result<(Vec<Statement<'_>>, HashMap<&str, usize>), AsmError> {
+    let mut out = vec![];
+    let mut labels = HashMap::new();
+
+    for (i, text) in source.lines().enumerate() {
+        let line = i + 1;
+        let code = text.split_once('#').map_or(text, |(code, _)| code);
+
+        for mut piece in code.split(';').map(str::trim) {
+            while let Some((label, rest)) = piece.split_once(':') {
+                let label = label.trim();
+                if !is_label(label) {
+                    return error(line, format!("{label:?} isn't a valid label"));
+                }
+                if labels.insert(label, out.len() * 2).is_some() {
+                    return error(line, format!("Label {label:?} is defined twice"));
+                }
+                piece = rest.trim();
+            }
+            if piece.is_empty() {
+                continue;
+            }
+
+            let mut words = piece.split_whitespace();
+            let mnemonic = words.next().unwrap();
+            let operand = words.next();
+            if let Some(extra) = words.next() {
+                return error(line, format!("Unexpected {extra:?} after the operand"));
+            }
+            out.push(Statement {
+                line,
+                mnemonic,
+                operand,
+            });
+        }
+    }
+    Ok((out, labels))
+}
+
+fn literal(operand: &str, labels: &HashMap<&str, usize>) -> Option<u8> {
+    match labels.get(operand) {
+        Some(&address) => u8::try_from(address).ok(),
+        None => operand.parse().ok(),
+    }
+    .filter(|&n| n < 8)
+}
+
+fn combo(operand: &str) -> Option<u8> {
+    match operand {
+        "a" => Some(4),
+        "b" => Some(5),
+        "c" => Some(6),
+        // How the disassembler shows the reserved combo operand
+        "?7" => Some(7),
+        _ => operand.parse().ok().filter(|&n| n < 4),
+    }
+}
+
+// Mnemonics as the disassembler prints them, for example "loop: adv 3; out a; jnz loop"
+pub fn assemble(source: &str) -> Result<Vec<OpCode>, AsmError> {
+    let (statements, labels) = statements(source)?;
+    let mut program = Vec::with_capacity(statements.len() * 2);
+
+    for Statement {
+        line,
+        mnemonic,
+        operand,
+    } in statements
+    {
+        let Some(&instruction) = Instruction::ALL
+            .iter()
+            .find(|i| i.mnemonic() == mnemonic.to_ascii_lowercase())
+        else {
+            return error(line, format!("Unknown instruction {mnemonic:?}"));
+        };
+
+        let value = match (instruction, operand) {
+            (Instruction::BXC, None) => Some(0),
+            (_, None) => return error(line, format!("{mnemonic} needs an operand")),
+            (_, Some(operand)) if instruction.takes_combo() => combo(operand),
+            (_, Some(operand)) => literal(operand, &labels),
+        };
+        let Some(value) = value else {
+            let kind = if instruction.takes_combo() {
+                "a combo operand (0-3, a, b or c)"
+            } else {
+                "a literal operand (0-7 or a label at 0-7)"
+            };
+            return error(line, format!("{mnemonic} needs {kind}, not {operand:?}"));
+        };
+
+        program.extend([instruction.opcode(), OpCode(value)]);
+    }
+    Ok(program)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::aoc::vm::disassemble;
+
+    #[test]
+    fn test_assemble() {
+        let ops = |ops: &[u8]| ops.iter().copied().map(OpCode).collect::<Vec<_>>();
+
+        assert_eq!(
+            assemble("loop: adv 3; out a; jnz loop"),
+            Ok(ops(&[0, 3, 5, 4, 3, 0]))
+        );
+        assert_eq!(
+            assemble(
+                "
+                # b and c from the bottom of a
+                start:
+                    bst a      # b = a & 7
+                    cdv b
+                    bxc
+                    out b; adv 3
+                    JNZ start"
+            ),
+            Ok(ops(&[2, 4, 7, 5, 4, 0, 5, 5, 0, 3, 3, 0]))
+        );
+
+        assert_eq!(assemble("adv 3\nadv a b").unwrap_err().line, 2);
+        assert!(assemble("bst 7").is_err());
+        assert!(assemble("jnz nowhere").is_err());
+        assert!(assemble("x: out a\nx: out b").is_err());
+        assert!(assemble("mul a").is_err());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let program: Vec<OpCode> = (0..8)
+            .flat_map(|opcode| (0..8).flat_map(move |operand| [OpCode(opcode), OpCode(operand)]))
+            .collect();
+
+        let source = disassemble(&program).unwrap();
+        assert_eq!(assemble(&source), Ok(program));
+    }
+}
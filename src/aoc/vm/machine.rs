@@ -0,0 +1,320 @@
+use std::{collections::VecDeque, sync::Arc};
+
+use super::{execute, Computer, Instruction, OpCode, Operand, Outcome, VmError};
+
+// What an instruction asks the machine to do next
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Effect {
+    Next,
+    Jump(usize),
+    // Leaves ip where it is so the instruction runs again once there's input
+    Wait,
+    Halt,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Running,
+    WaitingForInput,
+    Halted,
+}
+
+// An instruction set decodes instructions out of a machine's memory and runs them against it
+pub trait InstructionSet: Sized {
+    // A cell of memory
+    type Word: Clone;
+    // A register, input or output value
+    type Value: Copy + Default;
+    type Op;
+    type Error;
+
+    // The instruction at ip and how many words it takes up, None past the end of the program
+    fn decode(machine: &Machine<Self>) -> Result<Option<(Self::Op, usize)>, Self::Error>;
+
+    fn execute(machine: &mut Machine<Self>, op: Self::Op) -> Result<Effect, Self::Error>;
+}
+
+// Memory is shared between clones until one of them writes to it, so snapshots are cheap and
+// can be explored from several threads at once
+pub struct Machine<S: InstructionSet> {
+    pub registers: Vec<S::Value>,
+    memory: Arc<Vec<S::Word>>,
+    pub ip: usize,
+    pub input: VecDeque<S::Value>,
+    pub output: VecDeque<S::Value>,
+    pub steps: usize,
+    halted: bool,
+}
+
+impl<S: InstructionSet> Clone for Machine<S> {
+    fn clone(&self) -> Self {
+        Self {
+            registers: self.registers.clone(),
+            memory: Arc::clone(&self.memory),
+            ip: self.ip,
+            input: self.input.clone(),
+            output: self.output.clone(),
+            steps: self.steps,
+            halted: self.halted,
+        }
+    }
+}
+
+pub struct Snapshot<S: InstructionSet>(Machine<S>);
+
+impl<S: InstructionSet> Machine<S> {
+    pub fn new(memory: Vec<S::Word>, registers: usize) -> Self {
+        Self {
+            registers: vec![S::Value::default(); registers],
+            memory: Arc::new(memory),
+            ip: 0,
+            input: VecDeque::new(),
+            output: VecDeque::new(),
+            steps: 0,
+            halted: false,
+        }
+    }
+
+    pub fn memory(&self) -> &[S::Word] {
+        &self.memory
+    }
+
+    // Copies memory first if a snapshot or clone still shares it
+    pub fn memory_mut(&mut self) -> &mut Vec<S::Word> {
+        Arc::make_mut(&mut self.memory)
+    }
+
+    pub fn fetch(&self, address: usize) -> Option<&S::Word> {
+        self.memory.get(address)
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.halted
+    }
+
+    pub fn push_input(&mut self, value: S::Value) {
+        self.input.push_back(value);
+    }
+
+    pub fn step(&mut self) -> Result<Status, S::Error> {
+        if self.halted {
+            return Ok(Status::Halted);
+        }
+        let Some((op, len)) = S::decode(self)? else {
+            self.halted = true;
+            return Ok(Status::Halted);
+        };
+
+        match S::execute(self, op)? {
+            Effect::Next => self.ip += len,
+            Effect::Jump(ip) => self.ip = ip,
+            Effect::Wait => return Ok(Status::WaitingForInput),
+            Effect::Halt => {
+                self.halted = true;
+                return Ok(Status::Halted);
+            }
+        }
+        self.steps += 1;
+        Ok(Status::Running)
+    }
+
+    // Runs until the machine halts or needs input that isn't there yet
+    pub fn run(&mut self) -> Result<Status, S::Error> {
+        loop {
+            match self.step()? {
+                Status::Running => {}
+                status => return Ok(status),
+            }
+        }
+    }
+
+    // Runs until there's an output to return, None if it stops first
+    pub fn next_output(&mut self) -> Result<Option<S::Value>, S::Error> {
+        while self.output.is_empty() {
+            if self.step()? != Status::Running {
+                break;
+            }
+        }
+        Ok(self.output.pop_front())
+    }
+
+    pub fn snapshot(&self) -> Snapshot<S> {
+        Snapshot(self.clone())
+    }
+
+    pub fn restore(&mut self, snapshot: &Snapshot<S>) {
+        *self = snapshot.0.clone();
+    }
+}
+
+// Day 17's computer as an instruction set, with registers a, b and c. It runs instructions
+// with the same code as Computer.
+pub struct ThreeBit;
+
+impl InstructionSet for ThreeBit {
+    type Word = OpCode;
+    type Value = isize;
+    type Op = (Instruction, Operand);
+    type Error = VmError;
+
+    fn decode(machine: &Machine<Self>) -> Result<Option<(Self::Op, usize)>, VmError> {
+        let Some(&opcode) = machine.fetch(machine.ip) else {
+            return Ok(None);
+        };
+        let operand = machine
+            .fetch(machine.ip + 1)
+            .ok_or(VmError::MissingOperand { ip: machine.ip })?;
+        Ok(Some(((opcode.into(), Operand(*operand)), 2)))
+    }
+
+    fn execute(
+        machine: &mut Machine<Self>,
+        (instruction, operand): Self::Op,
+    ) -> Result<Effect, VmError> {
+        let ip = machine.ip;
+        let count = machine.registers.len();
+        let registers: &mut [isize; 3] = machine
+            .registers
+            .as_mut_slice()
+            .try_into()
+            .map_err(|_| VmError::RegisterCount { registers: count })?;
+
+        Ok(match execute(registers, instruction, operand, ip)? {
+            Outcome::Next => Effect::Next,
+            Outcome::Jump(ip) => Effect::Jump(ip),
+            Outcome::Output(n) => {
+                machine.output.push_back(n.into());
+                Effect::Next
+            }
+        })
+    }
+}
+
+impl Machine<ThreeBit> {
+    // With registers a, b and c all 0
+    pub fn three_bit(program: Vec<OpCode>) -> Self {
+        Self::new(program, 3)
+    }
+}
+
+impl From<&Computer> for Machine<ThreeBit> {
+    fn from(computer: &Computer) -> Self {
+        let mut machine = Machine::three_bit(computer.program.clone());
+        machine.registers.copy_from_slice(&computer.registers());
+        machine.ip = computer.ip;
+        machine
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::aoc::vm::asm::assemble;
+
+    // Adds up its inputs until it reads a 0, then outputs the total
+    struct Summer;
+
+    #[derive(Clone, Copy)]
+    enum SumOp {
+        Read,
+        Add,
+        Out,
+        JumpIfNonZero(usize),
+    }
+
+    impl InstructionSet for Summer {
+        type Word = SumOp;
+        type Value = i64;
+        type Op = SumOp;
+        type Error = ();
+
+        fn decode(machine: &Machine<Self>) -> Result<Option<(SumOp, usize)>, ()> {
+            Ok(machine.fetch(machine.ip).map(|&op| (op, 1)))
+        }
+
+        fn execute(machine: &mut Machine<Self>, op: SumOp) -> Result<Effect, ()> {
+            let r = &mut machine.registers;
+            Ok(match op {
+                SumOp::Read => match machine.input.pop_front() {
+                    Some(n) => {
+                        r[1] = n;
+                        Effect::Next
+                    }
+                    None => Effect::Wait,
+                },
+                SumOp::Add => {
+                    r[0] += r[1];
+                    Effect::Next
+                }
+                SumOp::Out => {
+                    machine.output.push_back(r[0]);
+                    Effect::Next
+                }
+                SumOp::JumpIfNonZero(ip) if r[1] != 0 => Effect::Jump(ip),
+                SumOp::JumpIfNonZero(_) => Effect::Next,
+            })
+        }
+    }
+
+    fn summer() -> Machine<Summer> {
+        use SumOp::*;
+        Machine::new(vec![Read, Add, JumpIfNonZero(0), Out], 2)
+    }
+
+    #[test]
+    fn test_input_and_snapshots() {
+        let mut machine = summer();
+        machine.push_input(3);
+        assert_eq!(machine.run(), Ok(Status::WaitingForInput));
+
+        let snapshot = machine.snapshot();
+        machine.push_input(4);
+        machine.push_input(0);
+        assert_eq!(machine.next_output(), Ok(Some(7)));
+        assert_eq!(machine.run(), Ok(Status::Halted));
+
+        machine.restore(&snapshot);
+        machine.input.extend([10, 0]);
+        assert_eq!(machine.next_output(), Ok(Some(13)));
+
+        let mut copy = machine.clone();
+        copy.memory_mut()[0] = SumOp::Out;
+        assert!(matches!(machine.memory()[0], SumOp::Read));
+    }
+
+    #[test]
+    fn test_three_bit() {
+        let program = assemble("loop: adv 1; out a; jnz loop").unwrap();
+        let computer = Computer::new(2024, 0, 0, program);
+
+        let mut machine = Machine::<ThreeBit>::from(&computer);
+        assert_eq!(machine.run(), Ok(Status::Halted));
+
+        let output: Vec<_> = machine.output.iter().map(|&n| n as u8).collect();
+        assert_eq!(output, computer.clone().output().unwrap());
+        assert_eq!(machine.registers[0], 0);
+
+        let mut machine = Machine::<ThreeBit>::new(assemble("out 1").unwrap(), 2);
+        assert_eq!(machine.run(), Err(VmError::RegisterCount { registers: 2 }));
+    }
+
+    #[test]
+    fn test_parallel_snapshots() {
+        use rayon::prelude::*;
+
+        let program = assemble("bst a; out b; adv 3; jnz 0").unwrap();
+        let snapshot = Machine::three_bit(program).snapshot();
+
+        let outputs: Vec<Vec<isize>> = (0..8)
+            .into_par_iter()
+            .map(|a| {
+                let mut machine = Machine::three_bit(vec![]);
+                machine.restore(&snapshot);
+                machine.registers[0] = a << 3 | 7;
+                machine.run().unwrap();
+                machine.output.into()
+            })
+            .collect();
+        assert_eq!(outputs[5], vec![7, 5]);
+    }
+}
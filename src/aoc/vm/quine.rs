@@ -0,0 +1,227 @@
+use std::fmt::Display;
+
+use super::{disassemble_one, ComboOperand, Computer, Instruction, Operand, VmError};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QuineError {
+    // The program isn't a "loop while a != 0, shifting a by 3" program, and why
+    Shape(String),
+    NoSolution,
+    Vm(VmError),
+}
+
+impl Display for QuineError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QuineError::Shape(why) => write!(f, "Program isn't a quine-shaped loop: {why}"),
+            QuineError::NoSolution => write!(f, "No value of a makes the program output itself"),
+            QuineError::Vm(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for QuineError {}
+
+impl From<VmError> for QuineError {
+    fn from(e: VmError) -> Self {
+        Self::Vm(e)
+    }
+}
+
+// Largest program whose a still fits in an isize at 3 bits per output
+const MAX_OUTPUTS: usize = (isize::BITS as usize - 1) / 3;
+
+fn shape_error(ip: usize, instruction: Instruction, operand: Operand, why: &str) -> QuineError {
+    QuineError::Shape(format!(
+        "{why} at {ip} ({})",
+        disassemble_one(instruction, operand)
+    ))
+}
+
+// Checks that the program is a single loop ending in jnz 0, which outputs once and only
+// changes a with one adv 3, and which sets b and c from a before using them. Each pass then
+// outputs a digit depending only on a, and drops the bottom 3 bits of a.
+pub fn check_shape(computer: &Computer) -> Result<(), QuineError> {
+    let program = &computer.program;
+    if program.is_empty() {
+        return Err(QuineError::Shape("the program is empty".to_string()));
+    }
+    if program.len() % 2 == 1 {
+        return Err(VmError::MissingOperand {
+            ip: program.len() - 1,
+        }
+        .into());
+    }
+    let (mut outs, mut shifts) = (0, 0);
+    let mut written = [true, false, false];
+    let last = program.len() - 2;
+
+    for ip in (0..program.len()).step_by(2) {
+        let instruction: Instruction = program[ip].into();
+        let operand = Operand(program[ip + 1]);
+        let error = |why| Err(shape_error(ip, instruction, operand, why));
+
+        let mut reads = vec![];
+        if instruction.takes_combo() {
+            match operand.combo() {
+                Some(ComboOperand::B) => reads.push(1),
+                Some(ComboOperand::C) => reads.push(2),
+                Some(_) => {}
+                None => return Err(VmError::ReservedOperand { ip }.into()),
+            }
+        }
+        let writes = match instruction {
+            Instruction::ADV => {
+                if operand.combo() != Some(ComboOperand::Literal(3)) {
+                    return error("a may only be shifted by 3");
+                }
+                shifts += 1;
+                None
+            }
+            Instruction::BXL => {
+                reads.push(1);
+                Some(1)
+            }
+            Instruction::BXC => {
+                reads.extend([1, 2]);
+                Some(1)
+            }
+            Instruction::BST | Instruction::BDV => Some(1),
+            Instruction::CDV => Some(2),
+            Instruction::OUT => {
+                outs += 1;
+                None
+            }
+            Instruction::JNZ => {
+                if ip != last || operand.literal() != 0 {
+                    return error("the only jump must be jnz 0 at the end");
+                }
+                None
+            }
+        };
+
+        if reads.iter().any(|&r| !written[r]) {
+            return error("b and c must be set from a before they are used");
+        }
+        if let Some(w) = writes {
+            written[w] = true;
+        }
+    }
+
+    if Instruction::from(program[last]) != Instruction::JNZ {
+        return Err(QuineError::Shape(
+            "the program must end in jnz 0".to_string(),
+        ));
+    }
+    match (outs, shifts) {
+        (1, 1) => Ok(()),
+        _ => Err(QuineError::Shape(format!(
+            "the loop must output once and shift a once, it outputs {outs} times and shifts {shifts} times"
+        ))),
+    }
+}
+
+// The digit one pass of the loop outputs when it starts with a
+fn first_output(computer: &Computer, a: isize) -> Result<Option<u8>, VmError> {
+    let mut computer = computer.clone();
+    computer.a = a;
+    computer.ip = 0;
+    computer.steps = 0;
+    computer.step_limit = Some(computer.program.len());
+    let first = computer.iter_output().next();
+    first.transpose()
+}
+
+// The smallest a which makes the program output itself.
+// Works backwards from the last digit, since the pass which outputs it only sees the top 3 bits
+// of a, and each earlier pass sees 3 more bits below those already chosen.
+pub fn find_quine(computer: &Computer) -> Result<isize, QuineError> {
+    check_shape(computer)?;
+    let target: Vec<u8> = computer.program.iter().map(|op| op.0).collect();
+    if target.len() > MAX_OUTPUTS {
+        return Err(QuineError::Shape(format!(
+            "{} outputs need more than {} bits of a",
+            target.len(),
+            isize::BITS - 1
+        )));
+    }
+
+    let mut candidates = vec![0];
+    for &digit in target.iter().rev() {
+        let mut next = vec![];
+        for high in candidates {
+            for low in 0..8 {
+                let a = high << 3 | low;
+                // a of 0 would have stopped the loop a pass earlier
+                if a != 0 && first_output(computer, a)? == Some(digit) {
+                    next.push(a);
+                }
+            }
+        }
+        candidates = next;
+    }
+
+    for a in candidates {
+        let mut check = computer.clone();
+        check.a = a;
+        if check.output()? == target {
+            return Ok(a);
+        }
+    }
+    Err(QuineError::NoSolution)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::aoc::vm::asm::assemble;
+
+    // bst a; cdv b; bxc; cdv b; adv 3; out c; jnz 0
+    const WIDE_SHIFT: [u8; 14] = [2, 4, 7, 5, 4, 0, 7, 5, 0, 3, 5, 6, 3, 0];
+
+    fn computer(program: &[u8]) -> Computer {
+        let mut computer = Computer::default();
+        computer.try_set_program(program.iter().copied()).unwrap();
+        computer
+    }
+
+    #[test]
+    fn test_find_quine() {
+        assert_eq!(find_quine(&computer(&[0, 3, 5, 4, 3, 0])), Ok(117440));
+
+        let program = assemble("loop: bst a; bxl 3; cdv b; adv 3; bxc; out b; jnz loop").unwrap();
+        let mut written = Computer::new(0, 0, 0, program);
+        let a = find_quine(&written).unwrap();
+        written.a = a;
+        assert_eq!(
+            written.output().unwrap(),
+            written.program.iter().map(|op| op.0).collect::<Vec<_>>()
+        );
+
+        let program = [2, 4, 1, 1, 7, 5, 4, 4, 1, 4, 0, 3, 5, 5, 3, 0];
+        let a = find_quine(&computer(&program)).unwrap();
+        let mut check = computer(&program);
+        check.a = a;
+        assert_eq!(check.output().unwrap(), program);
+
+        // Shifts by b ^ c, which can be far wider than a
+        assert_eq!(
+            find_quine(&computer(&WIDE_SHIFT)),
+            Err(QuineError::NoSolution)
+        );
+    }
+
+    #[test]
+    fn test_shape() {
+        let shape =
+            |program: &[u8]| matches!(check_shape(&computer(program)), Err(QuineError::Shape(_)));
+
+        assert!(shape(&[0, 3, 5, 4]));
+        assert!(shape(&[0, 2, 5, 4, 3, 0]));
+        assert!(shape(&[0, 3, 5, 4, 5, 4, 3, 0]));
+        assert!(shape(&[0, 3, 5, 5, 3, 0]));
+        assert!(shape(&[3, 0, 0, 3, 5, 4, 3, 0]));
+        assert!(check_shape(&computer(&[2, 4, 1, 5, 7, 5, 1, 6, 0, 3, 4, 3, 5, 5, 3, 0])).is_ok());
+        assert!(check_shape(&computer(&WIDE_SHIFT)).is_ok());
+    }
+}
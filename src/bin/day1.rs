@@ -5,13 +5,7 @@ mod part1 {
     pub fn parse_input(input: &str) -> (Vec<usize>, Vec<usize>) {
         let pairs: Vec<_> = input
             .lines()
-            .map(|l| {
-                let mut l = l.split_ascii_whitespace();
-                (
-                    l.next().unwrap().parse().unwrap(),
-                    l.next().unwrap().parse().unwrap(),
-                )
-            })
+            .map(|l| aoc2024::scan!(l, "{} {}", usize, usize).unwrap())
             .collect();
 
         (
@@ -1,15 +1,19 @@
-use aoc::grid::{Direction, Grid, GridCell, Point};
+use aoc::grid::{bitgrid::BitGrid, Direction, Grid, GridCell};
 use aoc2024::aoc;
-use std::collections::{HashSet, VecDeque};
+use std::collections::VecDeque;
 
 type Map = Grid<Vec<u8>>;
 type MapCell<'a> = GridCell<'a, Vec<u8>>;
 
+fn parse_input(input: &[u8]) -> Map {
+    aoc::bytes::grid_map(input, |b| b - b'0').unwrap_or_else(|e| panic!("{e}"))
+}
+
 mod part1 {
     use super::*;
 
     fn trailheads(start: &MapCell) -> usize {
-        let mut checked = HashSet::<Point>::new();
+        let mut checked = BitGrid::for_grid(start.grid());
         let mut queue = VecDeque::<GridCell<'_, _>>::new();
         queue.push_back(*start);
         let mut count = 0;
@@ -22,7 +26,7 @@ mod part1 {
             {
                 let v = *cell.value();
                 let p = cell.point();
-                if checked.contains(&p) {
+                if checked.test(&p) {
                     continue;
                 }
                 if v == value + 1 {
@@ -31,7 +35,7 @@ mod part1 {
                     } else {
                         queue.push_back(cell);
                     }
-                    checked.insert(p);
+                    checked.set(&p);
                 }
             }
         }
@@ -52,8 +56,8 @@ mod part1 {
 
         #[test]
         fn test_example() {
-            let input = aoc::example::example_string("day10.txt");
-            assert_eq!(calculate(&input.parse().unwrap()), 36);
+            let input = aoc::example::example_bytes("day10.txt");
+            assert_eq!(calculate(&parse_input(&input)), 36);
         }
     }
 }
@@ -99,8 +103,8 @@ mod part2 {
 
         #[test]
         fn test_example() {
-            let input = aoc::example::example_string("day10.txt");
-            assert_eq!(calculate(&input.parse().unwrap()), 81);
+            let input = aoc::example::example_bytes("day10.txt");
+            assert_eq!(calculate(&parse_input(&input)), 81);
         }
     }
 }
@@ -108,8 +112,7 @@ mod part2 {
 fn main() {
     let cli = aoc::cli::parse();
 
-    let input = cli.input_string();
-    let map: Map = input.parse().expect("Could not parse map");
+    let map = parse_input(&cli.input_bytes());
     println!("Part 1: {}", part1::calculate(&map));
     println!("Part 2: {}", part2::calculate(&map));
 }
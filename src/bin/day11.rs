@@ -1,11 +1,9 @@
 use std::{collections::HashMap, fmt::Display, str::FromStr};
 
-use aoc2024::aoc;
-
-fn split_pow10(n: usize, pow10: u32) -> (usize, usize) {
-    let div = 10usize.pow(pow10);
-    (n / div, n % div)
-}
+use aoc2024::{
+    aoc,
+    quant::{digit_count, split_digits_at},
+};
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
 enum Blink {
@@ -18,10 +16,10 @@ fn blink(n: usize) -> Blink {
         return Blink::One(1);
     }
 
-    let pow10 = n.ilog10() + 1;
+    let digits = digit_count(n);
 
-    if pow10 % 2 == 0 {
-        let (left, right) = split_pow10(n, pow10 / 2);
+    if digits.is_multiple_of(2) {
+        let (left, right) = split_digits_at(n, digits / 2);
         Blink::Two(left, right)
     } else {
         Blink::One(n * 2024)
@@ -154,10 +152,10 @@ mod slow_but_fun {
                         return;
                     }
 
-                    let pow10 = n.ilog10() + 1;
+                    let digits = digit_count(*n);
 
-                    if pow10 % 2 == 0 {
-                        let (left, right) = split_pow10(*n, pow10 / 2);
+                    if digits.is_multiple_of(2) {
+                        let (left, right) = split_digits_at(*n, digits / 2);
                         *self = StoneList::cons(StoneList::Stone(left), StoneList::Stone(right));
                     } else {
                         *n *= 2024;
@@ -1,6 +1,13 @@
 use std::fmt::Display;
 
-use aoc2024::aoc;
+use aoc2024::{
+    aoc::{
+        self,
+        input::Input,
+        linalg::{self, Solution},
+    },
+    quant::ext_gcd,
+};
 
 #[derive(Clone, Copy)]
 struct Button {
@@ -24,24 +31,30 @@ impl Display for Game {
 }
 
 mod parse {
-    use aoc2024::aoc::parse::parse_number;
-    use nom::{
-        bytes::complete::tag, character::complete::char, multi::separated_list0, sequence::tuple,
+    use aoc2024::aoc::{
+        input::Input,
+        parse::{labelled, labelled_coords},
     };
+    use nom::{character::complete::line_ending, combinator::map, sequence::tuple};
 
     use super::{Button, Game};
 
-    pub fn parse_input(s: &str) -> Vec<Game> {
-        separated_list0(tag("\n\n"), parse_game)(s).unwrap().1
+    pub fn parse_input(input: &Input) -> Vec<Game> {
+        input
+            .sections()
+            .iter()
+            .map(|section| section.parse_with(parse_game))
+            .collect::<Result<_, _>>()
+            .unwrap_or_else(|e| panic!("{e}"))
     }
 
     fn parse_game(s: &str) -> nom::IResult<&str, Game> {
         let (s, (a, _, b, _, (prize_x, prize_y))) = tuple((
-            parse_button,
-            char('\n'),
-            parse_button,
-            char('\n'),
-            parse_prize,
+            parse_button("Button A"),
+            line_ending,
+            parse_button("Button B"),
+            line_ending,
+            labelled("Prize", labelled_coords("X=", "Y=")),
         ))(s)?;
 
         Ok((
@@ -55,55 +68,93 @@ mod parse {
         ))
     }
 
-    fn parse_button(s: &str) -> nom::IResult<&str, Button> {
-        let (s, _) = nom::branch::alt((tag("Button A: X+"), tag("Button B: X+")))(s)?;
-        let (s, x) = parse_number(s)?;
-        let (s, _) = tag(", Y+")(s)?;
-        let (s, y) = parse_number(s)?;
+    fn parse_button<'a>(name: &'a str) -> impl FnMut(&'a str) -> nom::IResult<&'a str, Button> {
+        map(labelled(name, labelled_coords("X+", "Y+")), |(x, y)| {
+            Button { x, y }
+        })
+    }
+}
+
+static A_COST: isize = 3;
+static B_COST: isize = 1;
 
-        Ok((s, Button { x, y }))
+// The k with lo <= x + k * step <= hi, as an inclusive range
+fn k_range(x: i128, step: i128, lo: i128, hi: i128) -> Option<(i128, i128)> {
+    match step.signum() {
+        0 => (lo..=hi).contains(&x).then_some((i128::MIN, i128::MAX)),
+        1 => Some((-(x - lo).div_euclid(step), (hi - x).div_euclid(step))),
+        _ => k_range(-x, -step, -hi, -lo),
     }
+}
 
-    fn parse_prize(s: &str) -> nom::IResult<&str, (isize, isize)> {
-        let (s, _) = tag("Prize: X=")(s)?;
-        let (s, x) = parse_number(s)?;
-        let (s, _) = tag(", Y=")(s)?;
-        let (s, y) = parse_number(s)?;
+// Collinear buttons, so only a * p + b * q = r along one axis matters. Every solution is
+// (a0 + k * q / g, b0 - k * p / g), and the cost is linear in k, so the cheapest one is at
+// an end of the range of k which keeps both counts in range.
+fn min_tokens_collinear(game: &Game, max_presses: Option<isize>) -> Option<isize> {
+    let (p, q, r) = if (game.a.x, game.b.x) != (0, 0) {
+        (game.a.x, game.b.x, game.prize_x)
+    } else if (game.a.y, game.b.y) != (0, 0) {
+        (game.a.y, game.b.y, game.prize_y)
+    } else {
+        // Neither button moves, and the prize must be at the start to have any solution
+        return Some(0);
+    };
+    let (p, q, r) = (p as i128, q as i128, r as i128);
 
-        Ok((s, (x, y)))
+    let (g, x, y) = ext_gcd(p, q);
+    let (g, x, y) = if g < 0 { (-g, -x, -y) } else { (g, x, y) };
+    if r % g != 0 {
+        return None;
     }
+    let (a0, b0) = (x * (r / g), y * (r / g));
+    let (a_step, b_step) = (q / g, -p / g);
+
+    let max = max_presses.map_or(i64::MAX as i128, |max| max as i128);
+    let (a_lo, a_hi) = k_range(a0, a_step, 0, max)?;
+    let (b_lo, b_hi) = k_range(b0, b_step, 0, max)?;
+    let (lo, hi) = (a_lo.max(b_lo), a_hi.min(b_hi));
+    if lo > hi {
+        return None;
+    }
+
+    let cost = |k: i128| (a0 + k * a_step) * A_COST as i128 + (b0 + k * b_step) * B_COST as i128;
+    Some(cost(lo).min(cost(hi)) as isize)
 }
 
-static A_COST: isize = 3;
-static B_COST: isize = 1;
+fn min_tokens(game: &Game, max_presses: Option<isize>) -> Option<isize> {
+    let solution = linalg::solve_2x2(
+        [
+            [game.a.x as i128, game.b.x as i128],
+            [game.a.y as i128, game.b.y as i128],
+        ],
+        [game.prize_x as i128, game.prize_y as i128],
+    )
+    .expect("Game too large to solve");
+
+    if let Solution::Infinite { .. } = solution {
+        return min_tokens_collinear(game, max_presses);
+    }
+    let presses = solution.integer()?;
+    let (a, b) = (presses[0] as isize, presses[1] as isize);
+    let in_range = |n: isize| n >= 0 && max_presses.is_none_or(|max| n <= max);
+
+    (in_range(a) && in_range(b)).then_some(a * A_COST + b * B_COST)
+}
 
 mod part1 {
     use super::*;
 
-    fn min_tokens(game: &Game) -> Option<isize> {
-        for a in 0..=100 {
-            let x = game.a.x * a;
-            let y = game.a.y * a;
-            for b in 0..=100 {
-                let x = x + game.b.x * b;
-                let y = y + game.b.y * b;
-
-                if x == game.prize_x && y == game.prize_y {
-                    return Some(a * A_COST + b * B_COST);
-                }
-            }
-        }
-        None
-    }
-
-    pub fn calculate(input: &str) -> isize {
+    pub fn calculate(input: &Input) -> isize {
         let games = parse::parse_input(input);
         #[cfg(test)]
         for game in &games {
             println!("{}\n\n", game);
         }
 
-        games.iter().filter_map(min_tokens).sum()
+        games
+            .iter()
+            .filter_map(|game| min_tokens(game, Some(100)))
+            .sum()
     }
 
     #[cfg(test)]
@@ -112,7 +163,7 @@ mod part1 {
 
         #[test]
         fn test_example() {
-            let input = aoc::example::example_string("day13.txt");
+            let input = aoc::example::example_input("day13.txt");
             assert_eq!(calculate(&input), 480);
         }
     }
@@ -123,32 +174,7 @@ mod part2 {
 
     static OFFSET: isize = 10000000000000;
 
-    fn solve(
-        Game {
-            a: Button { x: ax, y: ay },
-            b: Button { x: bx, y: by },
-            prize_x: px,
-            prize_y: py,
-        }: &Game,
-    ) -> Option<usize> {
-        // Shamelessly stolen from https://www.youtube.com/watch?v=-5J-DAsWuJc
-        // because I didn't remember systems of linear equations from school
-        let numerator = px * by - py * bx;
-        let denominator = ax * by - ay * bx;
-        if numerator % denominator != 0 {
-            return None;
-        }
-        let a_presses = numerator / denominator;
-
-        let numerator = px - ax * a_presses;
-        if numerator % bx != 0 {
-            return None;
-        }
-        let b_presses = numerator / bx;
-        Some((a_presses * A_COST + b_presses * B_COST) as usize)
-    }
-
-    pub fn calculate(input: &str) -> usize {
+    pub fn calculate(input: &Input) -> isize {
         parse::parse_input(input)
             .iter()
             .map(|game| Game {
@@ -157,7 +183,7 @@ mod part2 {
                 prize_x: game.prize_x + OFFSET,
                 prize_y: game.prize_y + OFFSET,
             })
-            .filter_map(|game| solve(&game))
+            .filter_map(|game| min_tokens(&game, None))
             .sum()
     }
 
@@ -167,8 +193,34 @@ mod part2 {
 fn main() {
     let cli = aoc::cli::parse();
 
-    let input = cli.input_string();
+    let input = cli.input();
 
     println!("Part 1: {}", part1::calculate(&input));
     println!("Part 2: {}", part2::calculate(&input));
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn game((ax, ay): (isize, isize), (bx, by): (isize, isize), prize: isize) -> Game {
+        Game {
+            a: Button { x: ax, y: ay },
+            b: Button { x: bx, y: by },
+            prize_x: prize,
+            prize_y: prize,
+        }
+    }
+
+    #[test]
+    fn test_collinear() {
+        // b alone is cheapest, unless it would take too many presses
+        assert_eq!(min_tokens(&game((2, 2), (3, 3), 12), None), Some(4));
+        assert_eq!(min_tokens(&game((2, 2), (3, 3), 12), Some(3)), Some(11));
+        // a moves 4 for 3 tokens, so it's worth using as much as possible
+        assert_eq!(min_tokens(&game((4, 4), (1, 1), 10), None), Some(8));
+        assert_eq!(min_tokens(&game((2, 2), (4, 4), 5), None), None);
+        assert_eq!(min_tokens(&game((2, 2), (4, 4), 100), Some(10)), None);
+        assert_eq!(min_tokens(&game((0, 0), (0, 5), 0), None), Some(0));
+    }
+}
@@ -40,19 +40,17 @@ mod test {
 }
 
 mod parse {
-    use aoc2024::aoc::parse::parse_number;
+    use aoc2024::aoc::parse::{lines_of, number_pair, parse_all};
     use nom::{
         bytes::complete::tag,
-        character::complete::{char, newline},
         combinator::map,
-        multi::separated_list1,
         sequence::{preceded, separated_pair},
     };
 
     use crate::{Robot, Vec2};
 
     pub fn parse_input(s: &str) -> Vec<Robot> {
-        separated_list1(newline, parse_robot)(s).unwrap().1
+        parse_all(lines_of(parse_robot), s).unwrap_or_else(|e| panic!("{e}"))
     }
 
     fn parse_robot(s: &str) -> nom::IResult<&str, Robot> {
@@ -63,10 +61,7 @@ mod parse {
     }
 
     fn parse_vec2(s: &str) -> nom::IResult<&str, Vec2> {
-        map(
-            separated_pair(parse_number, char(','), parse_number),
-            Vec2::from,
-        )(s)
+        map(number_pair, Vec2::from)(s)
     }
 
     #[cfg(test)]
@@ -153,46 +148,64 @@ mod part1 {
 }
 
 mod part2 {
-    use std::{collections::HashSet, path::PathBuf};
+    use std::path::PathBuf;
 
-    use aoc::grid::Grid;
+    use aoc::{
+        grid::{renderer::Style, Grid, Point},
+        render::{Image, BLACK, WHITE},
+    };
+    use aoc2024::quant::crt;
 
     use super::*;
 
-    fn draw(robots: &[Robot]) -> String {
-        let grid = Grid::fill(WIDTH, HEIGHT, ' ');
+    fn points(robots: &[Robot]) -> impl Iterator<Item = Point> + '_ {
+        robots
+            .iter()
+            .map(|r| Point::try_from(r.position).expect("Robots stay on the grid"))
+    }
 
-        for r in robots {
-            *grid
-                .cell_at(r.position.x as usize, r.position.y as usize)
-                .unwrap()
-                .value_mut() = '*';
-        }
+    fn draw(robots: &[Robot]) -> String {
+        Grid::fill(WIDTH, HEIGHT, ' ')
+            .render()
+            .mark(points(robots), '*', Style::new())
+            .plain()
+            .to_string()
+    }
 
-        format!("{grid}")
+    fn variance(values: impl Iterator<Item = isize> + Clone) -> isize {
+        let n = values.clone().count() as isize;
+        let mean = values.clone().sum::<isize>() / n;
+        values.map(|v| (v - mean) * (v - mean)).sum::<isize>() / n
     }
 
-    fn tick(robots: &[Robot]) -> Vec<Robot> {
-        robots.iter().map(|r| r.run(1, WIDTH, HEIGHT)).collect()
+    // The robots bunch up on the x axis every WIDTH ticks and on the y axis every
+    // HEIGHT ticks, the picture is where both line up
+    fn most_clustered(robots: &[Robot], period: usize, axis: fn(&Vec2) -> isize) -> isize {
+        (0..period)
+            .min_by_key(|&t| {
+                variance(
+                    robots
+                        .iter()
+                        .map(|r| axis(&r.run(t, WIDTH, HEIGHT).position)),
+                )
+            })
+            .unwrap() as isize
     }
 
     pub fn calculate(input: &str) -> usize {
-        let mut robots = parse::parse_input(input);
+        let robots = parse::parse_input(input);
+        let tx = most_clustered(&robots, WIDTH, |p| p.x);
+        let ty = most_clustered(&robots, HEIGHT, |p| p.y);
+        let (i, _) = crt([(tx, WIDTH as isize), (ty, HEIGHT as isize)]).unwrap();
+        let i = i as usize;
+
+        let robots: Vec<_> = robots.iter().map(|r| r.run(i, WIDTH, HEIGHT)).collect();
         let output_root = &PathBuf::from("doodles/");
         let _ = std::fs::create_dir(output_root);
-        let mut i = 0;
-        loop {
-            let filepath = output_root.join(format!("{i}.txt"));
-            let positions: HashSet<_> = robots.iter().map(|r| r.position).collect();
-            if positions.len() == robots.len() {
-                let picture = draw(&robots);
-                let _ = std::fs::write(&filepath, &picture);
-                println!("{picture}");
-                break i;
-            }
-            robots = tick(&robots);
-            i += 1;
-        }
+        let image = Image::from_points(WIDTH, HEIGHT, points(&robots), WHITE, BLACK);
+        let _ = image.scale(4).write(output_root.join(format!("{i}.png")));
+        println!("{}", draw(&robots));
+        i
     }
 }
 
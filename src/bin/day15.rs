@@ -6,8 +6,11 @@ use std::{
 
 use aoc2024::aoc::{
     self,
-    grid::{Axis, Direction, Grid, Point},
+    grid::{renderer::Style, Axis, Direction, Grid, Point},
+    input::Input,
+    player::Player,
 };
+use colored::Color;
 
 mod parse {
     use aoc2024::aoc::grid::Direction;
@@ -41,7 +44,7 @@ mod part1 {
     }
 
     impl FromStr for Tile {
-        type Err = ();
+        type Err = String;
 
         fn from_str(s: &str) -> Result<Self, Self::Err> {
             match s {
@@ -49,7 +52,7 @@ mod part1 {
                 "@" => Ok(Self::Robot),
                 "O" => Ok(Self::Obstacle),
                 "#" => Ok(Self::Wall),
-                _ => Err(()),
+                _ => Err("not a tile".to_string()),
             }
         }
     }
@@ -85,10 +88,10 @@ mod part1 {
             .point()
     }
 
-    pub fn calculate(input: &str) -> usize {
-        let (map, moves) = input.split_once("\n\n").unwrap();
-        let moves = parse::parse_moves(moves);
-        let map = map.trim().parse::<Map>().unwrap();
+    pub fn calculate(input: &Input) -> usize {
+        let (map, moves) = input.sections2().unwrap_or_else(|e| panic!("{e}"));
+        let moves = parse::parse_moves(moves.text());
+        let map: Map = map.grid().unwrap_or_else(|e| panic!("{e}"));
         let mut robot_point = find_robot_point(&map);
 
         'moves: for direction in moves {
@@ -142,13 +145,13 @@ mod part1 {
 
         #[test]
         fn test_example() {
-            let input = aoc::example::example_string("day15.txt");
+            let input = aoc::example::example_input("day15.txt");
             assert_eq!(calculate(&input), 10092);
         }
 
         #[test]
         fn test_example_small() {
-            let input = aoc::example::example_string("day15_small.txt");
+            let input = aoc::example::example_input("day15_small.txt");
             assert_eq!(calculate(&input), 2028);
         }
     }
@@ -169,7 +172,7 @@ mod part2 {
     }
 
     impl FromStr for Tile {
-        type Err = ();
+        type Err = String;
 
         fn from_str(s: &str) -> Result<Self, Self::Err> {
             match s {
@@ -178,7 +181,7 @@ mod part2 {
                 "[" => Ok(Self::ObstacleLeft),
                 "]" => Ok(Self::ObstacleRight),
                 "#" => Ok(Self::Wall),
-                _ => Err(()),
+                _ => Err("not a tile".to_string()),
             }
         }
     }
@@ -229,92 +232,155 @@ mod part2 {
             .point()
     }
 
-    fn evaluate(input: &str) -> Map {
-        let (map, moves) = input.split_once("\n\n").unwrap();
-        let moves = parse::parse_moves(moves);
-        let map = widen(map.trim()).parse::<Map>().unwrap();
-        let mut robot_point = find_robot_point(&map);
+    fn parse_input(input: &Input) -> (Map, Vec<Direction>) {
+        let (map, moves) = input.sections2().unwrap_or_else(|e| panic!("{e}"));
+        let moves = parse::parse_moves(moves.text());
+        let map = widen(map.text()).parse::<Map>().unwrap();
+        (map, moves)
+    }
 
-        'moves: for direction in moves {
-            let mut queue = VecDeque::new();
-            let mut to_move = vec![];
-            let mut checked = HashSet::new();
+    // Moves the robot, pushing any boxes in the way, and gives where it ends up
+    fn push(map: &Map, robot_point: Point, direction: Direction) -> Point {
+        let mut queue = VecDeque::new();
+        let mut to_move = vec![];
+        let mut checked = HashSet::new();
 
-            queue.push_back(robot_point);
+        queue.push_back(robot_point);
 
-            while let Some(point) = queue.pop_front() {
-                if !checked.insert(point) {
-                    continue;
+        while let Some(point) = queue.pop_front() {
+            if !checked.insert(point) {
+                continue;
+            }
+            let cell = point.on(map).expect("Point in queue must be on map");
+            let tile = *cell.value();
+
+            match tile {
+                Tile::Empty => continue,
+                Tile::Robot => {
+                    queue.push_back(
+                        (point + direction).expect("Robot should not be at edge of map"),
+                    );
                 }
-                let cell = point.on(&map).expect("Point in queue must be on map");
-                let tile = *cell.value();
-
-                match tile {
-                    Tile::Empty => continue,
-                    Tile::Robot => {
+                Tile::ObstacleLeft => match direction.axis() {
+                    Axis::Vertical => {
+                        queue.push_back(
+                            (point + direction).expect("ObstacleLeft should not be at edge of map"),
+                        );
                         queue.push_back(
-                            (point + direction).expect("Robot should not be at edge of map"),
+                            (point + Direction::Right)
+                                .expect("ObstacleLeft should not be at edge of map"),
                         );
                     }
-                    Tile::ObstacleLeft => match direction.axis() {
-                        Axis::Vertical => {
-                            queue.push_back(
-                                (point + direction)
-                                    .expect("ObstacleLeft should not be at edge of map"),
-                            );
-                            queue.push_back(
-                                (point + Direction::Right)
-                                    .expect("ObstacleLeft should not be at edge of map"),
-                            );
-                        }
-                        Axis::Horizontal => queue.push_back(
-                            (point + direction).expect("ObstacleLeft should not be at edge of map"),
-                        ),
-                    },
-                    Tile::ObstacleRight => match direction.axis() {
-                        Axis::Vertical => {
-                            queue.push_back(
-                                (point + direction)
-                                    .expect("ObstacleRight should not be at edge of map"),
-                            );
-                            queue.push_back(
-                                (point + Direction::Left)
-                                    .expect("ObstacleRight should not be at edge of map"),
-                            );
-                        }
-                        Axis::Horizontal => queue.push_back(
+                    Axis::Horizontal => queue.push_back(
+                        (point + direction).expect("ObstacleLeft should not be at edge of map"),
+                    ),
+                },
+                Tile::ObstacleRight => match direction.axis() {
+                    Axis::Vertical => {
+                        queue.push_back(
                             (point + direction)
                                 .expect("ObstacleRight should not be at edge of map"),
-                        ),
-                    },
-                    Tile::Wall => continue 'moves,
-                }
-
-                to_move.push((point, tile));
+                        );
+                        queue.push_back(
+                            (point + Direction::Left)
+                                .expect("ObstacleRight should not be at edge of map"),
+                        );
+                    }
+                    Axis::Horizontal => queue.push_back(
+                        (point + direction).expect("ObstacleRight should not be at edge of map"),
+                    ),
+                },
+                Tile::Wall => return robot_point,
             }
 
-            let to_move_points: HashSet<_> = to_move.iter().map(|(p, _)| *p).collect();
+            to_move.push((point, tile));
+        }
 
-            for (point, tile) in to_move {
-                let new_point = (point + direction).unwrap();
-                *new_point.on(&map).unwrap().value_mut() = tile;
-                if matches!(tile, Tile::Robot) {
-                    robot_point = new_point;
-                }
-                if let Some(behind) = point - direction {
-                    if !to_move_points.contains(&behind) {
-                        *point.on(&map).unwrap().value_mut() = Tile::Empty;
-                    }
+        let to_move_points: HashSet<_> = to_move.iter().map(|(p, _)| *p).collect();
+        let mut robot_point = robot_point;
+
+        for (point, tile) in to_move {
+            let new_point = (point + direction).unwrap();
+            *new_point.on(map).unwrap().value_mut() = tile;
+            if matches!(tile, Tile::Robot) {
+                robot_point = new_point;
+            }
+            if let Some(behind) = point - direction {
+                if !to_move_points.contains(&behind) {
+                    *point.on(map).unwrap().value_mut() = Tile::Empty;
                 }
             }
         }
+        robot_point
+    }
+
+    fn evaluate(input: &Input) -> Map {
+        let (map, moves) = parse_input(input);
+        let mut robot_point = find_robot_point(&map);
+
+        for direction in moves {
+            robot_point = push(&map, robot_point, direction);
+        }
 
         #[cfg(test)]
         println!("\n{}", map);
         map
     }
 
-    pub fn calculate(input: &str) -> usize {
+    #[derive(Clone)]
+    struct Warehouse {
+        map: Map,
+        robot_point: Point,
+        next_move: usize,
+    }
+
+    pub fn play(input: &Input) -> std::io::Result<()> {
+        let (map, moves) = parse_input(input);
+        let warehouse = Warehouse {
+            robot_point: find_robot_point(&map),
+            map,
+            next_move: 0,
+        };
+
+        let step = |warehouse: &mut Warehouse| {
+            let Some(&direction) = moves.get(warehouse.next_move) else {
+                return false;
+            };
+            warehouse.robot_point = push(&warehouse.map, warehouse.robot_point, direction);
+            warehouse.next_move += 1;
+            true
+        };
+
+        let render = |warehouse: &Warehouse| {
+            let next = moves
+                .get(warehouse.next_move)
+                .map_or("none".to_string(), |direction| direction.to_string());
+            let map = warehouse
+                .map
+                .render()
+                .style(|tile| match tile {
+                    Tile::Wall => Style::fg(Color::BrightBlack),
+                    Tile::ObstacleLeft | Tile::ObstacleRight => Style::fg(Color::Cyan),
+                    _ => Style::default(),
+                })
+                .mark(
+                    [warehouse.robot_point],
+                    '@',
+                    Style::fg(Color::Yellow).bold(),
+                );
+            format!(
+                "{map}\nMove {}/{}, next {next}, score {}",
+                warehouse.next_move,
+                moves.len(),
+                score(&warehouse.map)
+            )
+        };
+
+        Player::new(warehouse, step, render).play()?;
+        Ok(())
+    }
+
+    pub fn calculate(input: &Input) -> usize {
         score(&evaluate(input))
     }
 
@@ -324,7 +390,7 @@ mod part2 {
 
         #[test]
         fn test_example() {
-            let input = aoc::example::example_string("day15.txt");
+            let input = aoc::example::example_input("day15.txt");
             let map = evaluate(&input);
             assert_eq!(
                 format!("{}", map).trim(),
@@ -346,7 +412,7 @@ mod part2 {
 
         #[test]
         fn test_example_part2() {
-            let input = aoc::example::example_string("day15_part2.txt");
+            let input = aoc::example::example_input("day15_part2.txt");
             let map = evaluate(&input);
             assert_eq!(
                 format!("{}", map).trim(),
@@ -384,7 +450,12 @@ mod part2 {
 fn main() {
     let cli = aoc::cli::parse();
 
-    let input = cli.input_string();
+    let input = cli.input();
+
+    if cli.play {
+        part2::play(&input).expect("Could not play the robot's moves");
+        return;
+    }
 
     println!("Part 1: {}", part1::calculate(&input));
     println!("Part 2: {}", part2::calculate(&input));
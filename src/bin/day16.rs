@@ -6,19 +6,16 @@ mod map {
         str::FromStr,
     };
 
-    use aoc2024::aoc::grid::{Direction, Grid};
+    use aoc2024::aoc::grid::Grid;
 
     pub type Map = Grid<Vec<Tile>>;
 
-    #[allow(dead_code)]
     #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
     pub enum Tile {
         Empty,
         Wall,
         Start,
         End,
-        Overlay(Direction),
-        Path,
     }
 
     impl Tile {
@@ -48,8 +45,6 @@ mod map {
                 Tile::Wall => '#',
                 Tile::Start => 'S',
                 Tile::End => 'E',
-                Tile::Overlay(direction) => direction.char(),
-                Tile::Path => 'O',
             })
         }
     }
@@ -222,22 +217,9 @@ mod part2 {
         map::{Map, Tile},
         state::State,
     };
-    use aoc2024::aoc::{
-        algo::djikstra::{Djikstra, QueueState},
-        grid::Direction,
-    };
+    use aoc2024::aoc::{algo::djikstra::Djikstra, grid::Direction};
     use std::collections::HashSet;
 
-    #[allow(dead_code)]
-    fn apply(map: &Map, state: &QueueState<State>) -> Map {
-        let map = map.clone();
-
-        for (point, direction) in state.path() {
-            *point.on(&map).unwrap().value_mut() = Tile::Overlay(direction);
-        }
-        map
-    }
-
     pub fn calculate(input: &str) -> usize {
         let map = input.parse::<Map>().unwrap();
 
@@ -262,14 +244,31 @@ mod part2 {
 
         #[cfg(test)]
         {
+            use aoc2024::aoc::grid::renderer::Style;
+            use colored::Color;
+
+            let walls = |tile: &Tile| match tile {
+                Tile::Wall => Style::fg(Color::BrightBlack),
+                _ => Style::new(),
+            };
+
             println!("Found {} end states", end_states.len());
             for state in &end_states {
-                println!("\n\nCost: {}\n{}\n", state.cost(), apply(&map, state));
-            }
-            for point in &points {
-                *point.on(&map).unwrap().value_mut() = Tile::Path;
+                println!(
+                    "\n\nCost: {}\n{}\n",
+                    state.cost(),
+                    map.render()
+                        .style(walls)
+                        .directions(state.path(), Style::fg(Color::Yellow))
+                );
             }
-            println!("{}", map);
+            println!(
+                "{}",
+                map.render()
+                    .style(walls)
+                    .mark(&points, 'O', Style::fg(Color::Green).bold())
+                    .rulers()
+            );
         }
 
         points.len()
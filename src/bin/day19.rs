@@ -1,32 +1,31 @@
-use aoc2024::aoc;
+use aoc2024::aoc::{self, input::Input};
 use std::collections::HashMap;
 
+// A line of comma separated towels, then a pattern per line
+fn parse_input(input: &Input) -> (Vec<&str>, Vec<&str>) {
+    let (towels, patterns) = input.sections2().unwrap_or_else(|e| panic!("{e}"));
+    let towels = towels.text().split(',').map(str::trim).collect();
+    let patterns = patterns.lines().collect();
+
+    (towels, patterns)
+}
+
 mod part1 {
     use super::*;
 
-    fn possible(towels: &[String], pattern: &str, cache: &mut HashMap<String, bool>) -> bool {
+    fn possible<'a>(towels: &[&str], pattern: &'a str, cache: &mut HashMap<&'a str, bool>) -> bool {
         if !cache.contains_key(pattern) {
             let val = pattern.is_empty()
                 || towels.iter().any(|towel| {
                     pattern.starts_with(towel) && possible(towels, &pattern[towel.len()..], cache)
                 });
-            cache.insert(pattern.to_string(), val);
+            cache.insert(pattern, val);
         }
         *cache.get(pattern).unwrap()
     }
 
-    pub fn calculate(input: &str) -> usize {
-        let mut lines = input.lines();
-        let towels: Vec<String> = lines
-            .next()
-            .unwrap()
-            .split(", ")
-            .map(str::to_string)
-            .collect();
-
-        lines.next();
-
-        let patterns: Vec<String> = lines.map(str::to_string).collect();
+    pub fn calculate(input: &Input) -> usize {
+        let (towels, patterns) = parse_input(input);
 
         patterns
             .iter()
@@ -41,7 +40,7 @@ mod part1 {
 
         #[test]
         fn test_example() {
-            let input = aoc::example::example_string("day19.txt");
+            let input = aoc::example::example_input("day19.txt");
             assert_eq!(calculate(&input), 6);
         }
     }
@@ -50,7 +49,11 @@ mod part1 {
 mod part2 {
     use super::*;
 
-    fn arrangements(towels: &[String], pattern: &str, cache: &mut HashMap<String, usize>) -> usize {
+    fn arrangements<'a>(
+        towels: &[&str],
+        pattern: &'a str,
+        cache: &mut HashMap<&'a str, usize>,
+    ) -> usize {
         if pattern.is_empty() {
             return 0;
         }
@@ -58,32 +61,22 @@ mod part2 {
             let count = towels
                 .iter()
                 .map(|towel| {
-                    if pattern == towel {
+                    if pattern == *towel {
                         1
-                    } else if pattern.starts_with(towel) {
-                        arrangements(towels, &pattern[towel.len()..], cache)
+                    } else if let Some(rest) = pattern.strip_prefix(towel) {
+                        arrangements(towels, rest, cache)
                     } else {
                         0
                     }
                 })
                 .sum();
-            cache.insert(pattern.to_string(), count);
+            cache.insert(pattern, count);
         }
         *cache.get(pattern).unwrap()
     }
 
-    pub fn calculate(input: &str) -> usize {
-        let mut lines = input.lines();
-        let towels: Vec<String> = lines
-            .next()
-            .unwrap()
-            .split(", ")
-            .map(str::to_string)
-            .collect();
-
-        lines.next();
-
-        let patterns: Vec<String> = lines.map(str::to_string).collect();
+    pub fn calculate(input: &Input) -> usize {
+        let (towels, patterns) = parse_input(input);
 
         patterns
             .iter()
@@ -97,7 +90,7 @@ mod part2 {
 
         #[test]
         fn test_example() {
-            let input = aoc::example::example_string("day19.txt");
+            let input = aoc::example::example_input("day19.txt");
             assert_eq!(calculate(&input), 16);
         }
     }
@@ -106,7 +99,7 @@ mod part2 {
 fn main() {
     let cli = aoc::cli::parse();
 
-    let input = cli.input_string();
+    let input = cli.input();
 
     println!("Part 1: {}", part1::calculate(&input));
     println!("Part 2: {}", part2::calculate(&input));
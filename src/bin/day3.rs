@@ -1,4 +1,7 @@
-use aoc2024::aoc;
+use aoc2024::aoc::{
+    self,
+    vm::machine::{Effect, InstructionSet, Machine},
+};
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 enum Instruction {
@@ -16,6 +19,33 @@ impl Instruction {
     }
 }
 
+// Runs the instructions with a running total in register 0 and whether mul is enabled in 1
+struct Corrupted;
+
+impl InstructionSet for Corrupted {
+    type Word = Instruction;
+    type Value = usize;
+    type Op = Instruction;
+    type Error = ();
+
+    fn decode(machine: &Machine<Self>) -> Result<Option<(Instruction, usize)>, ()> {
+        Ok(machine
+            .fetch(machine.ip)
+            .map(|&instruction| (instruction, 1)))
+    }
+
+    fn execute(machine: &mut Machine<Self>, instruction: Instruction) -> Result<Effect, ()> {
+        match instruction {
+            Instruction::Mul(_, _) => {
+                machine.registers[0] += machine.registers[1] * instruction.value()
+            }
+            Instruction::Do => machine.registers[1] = 1,
+            Instruction::Dont => machine.registers[1] = 0,
+        }
+        Ok(Effect::Next)
+    }
+}
+
 mod parse {
     use nom::branch::alt;
     use nom::bytes::complete::{tag, take_while_m_n};
@@ -104,21 +134,11 @@ mod part2 {
     use super::*;
 
     pub fn calculate(s: &str) -> usize {
-        parse_input(s)
-            .into_iter()
-            .fold((0, true), |(total, enabled), instruction| {
-                let enabled = match instruction {
-                    Instruction::Mul(_, _) => enabled,
-                    Instruction::Do => true,
-                    Instruction::Dont => false,
-                };
-
-                (
-                    total + if enabled { instruction.value() } else { 0 },
-                    enabled,
-                )
-            })
-            .0
+        let mut machine = Machine::<Corrupted>::new(parse_input(s), 2);
+        machine.registers[1] = 1;
+        machine.run().unwrap();
+
+        machine.registers[0]
     }
 
     #[cfg(test)]
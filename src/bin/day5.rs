@@ -1,70 +1,64 @@
-use aoc2024::aoc;
-use std::collections::{HashMap, HashSet};
-
-fn update_valid(update: &[usize], rules_after: &HashMap<usize, HashSet<usize>>) -> bool {
-    for i in 0..update.len() - 1 {
-        let page = update[i];
-        let rest = &update[i + 1..];
-        if let Some(banned_pages) = rules_after.get(&page) {
-            for nn in rest {
-                if banned_pages.contains(nn) {
-                    return false;
-                }
-            }
-        }
-    }
-
-    true
+use aoc2024::aoc::{
+    self,
+    graph::Graph,
+    input::{Input, InputError},
+};
+use aoc_macros::AocParse;
+
+// Rules are edges from the page that must come first
+fn update_valid(update: &[usize], rules: &Graph<usize>) -> bool {
+    update.iter().enumerate().all(|(i, page)| {
+        update[i + 1..]
+            .iter()
+            .all(|later| !rules.has_edge(later, page))
+    })
 }
-struct Input {
-    pub _rules_before: HashMap<usize, HashSet<usize>>,
-    pub rules_after: HashMap<usize, HashSet<usize>>,
+
+struct Manual {
+    pub rules: Graph<usize>,
     pub updates: Vec<Vec<usize>>,
 }
 
-fn parse_input(input: &str) -> Input {
-    let (rules, updates) = input.split_once("\n\n").unwrap();
-
-    let (_rules_before, rules_after): (
-        HashMap<usize, HashSet<usize>>,
-        HashMap<usize, HashSet<usize>>,
-    ) = rules
-        .trim()
-        .lines()
-        .map(|l| -> (usize, usize) {
-            let (before, after) = l.split_once('|').unwrap();
-            (before.parse().unwrap(), after.parse().unwrap())
-        })
-        .fold(
-            Default::default(),
-            |(mut before_acc, mut after_acc), (before, after)| {
-                before_acc.entry(before).or_default().insert(after);
-                after_acc.entry(after).or_default().insert(before);
-                (before_acc, after_acc)
-            },
-        );
-
-    let updates: Vec<Vec<usize>> = updates
-        .trim()
-        .lines()
-        .map(|l| l.split(',').map(|s| s.parse().unwrap()).collect())
+#[derive(AocParse)]
+#[aoc(format = "{}|{}")]
+struct Rule {
+    before: usize,
+    after: usize,
+}
+
+#[derive(AocParse)]
+#[aoc(format = "{}")]
+struct Update(Vec<usize>);
+
+fn parse_input(input: &Input) -> Result<Manual, InputError> {
+    let (rules, updates) = input.sections2()?;
+
+    let rules =
+        rules
+            .parse_lines::<Rule>()?
+            .into_iter()
+            .fold(Graph::directed(), |mut graph, rule| {
+                graph.add_edge(rule.before, rule.after, ());
+                graph
+            });
+
+    let updates = updates
+        .parse_lines::<Update>()?
+        .into_iter()
+        .map(|update| update.0)
         .collect();
 
-    Input {
-        _rules_before,
-        rules_after,
-        updates,
-    }
+    Ok(Manual { rules, updates })
 }
 
 mod part1 {
     use super::*;
 
-    pub fn calculate(input: &Input) -> usize {
+    pub fn calculate(input: &Manual) -> usize {
         input
             .updates
             .iter()
-            .filter(|update| update_valid(update, &input.rules_after))
+            .filter(|update| update_valid(update, &input.rules))
             .map(|update| update[update.len() / 2])
             .sum()
     }
@@ -75,9 +69,9 @@ mod part1 {
 
         #[test]
         fn test_example() {
-            let input = aoc::example::example_string("day5.txt");
+            let input = aoc::example::example_input("day5.txt");
 
-            assert_eq!(calculate(&parse_input(&input)), 143);
+            assert_eq!(calculate(&parse_input(&input).unwrap()), 143);
         }
     }
 }
@@ -85,65 +79,30 @@ mod part1 {
 mod part2 {
     use super::*;
 
-    pub fn calculate(input: &Input) -> usize {
+    pub fn calculate(input: &Manual) -> usize {
         input
             .updates
             .iter()
-            .filter_map(move |update| fix(update, &input.rules_after))
+            .filter_map(move |update| fix(update, &input.rules))
             .map(|update| update[update.len() / 2])
             .sum()
     }
 
-    fn fix(update: &[usize], rules_after: &HashMap<usize, HashSet<usize>>) -> Option<Vec<usize>> {
-        let mut out = Vec::from(update);
-        let l = out.len();
-        if update_valid(&out, rules_after) {
-            #[cfg(test)]
-            println!("Update already valid! {out:?}");
+    // Topologically sort the update's pages under the rules that mention two of them
+    fn fix(update: &[usize], rules: &Graph<usize>) -> Option<Vec<usize>> {
+        if update_valid(update, rules) {
             return None;
         }
 
-        let mut i = 0;
-
-        while i < l - 1 {
-            let mut update_idx: Option<usize> = None;
-            let page = out[i];
-            let Some(rules) = rules_after.get(&page) else {
-                #[cfg(test)]
-                println!("No rules found for {page}");
-                i += 1;
-                continue;
-            };
-            #[cfg(test)]
-            println!("Checking for swaps for {page} at {i}");
-
-            #[allow(clippy::needless_range_loop)]
-            for j in i + 1..out.len() {
-                let pj = out[j];
-                #[cfg(test)]
-                println!("  Checking for swap against {pj} at {j}");
-                if rules.contains(&pj) {
-                    #[cfg(test)]
-                    println!(
-                        "    {} cannot have {} after, setting swap to {}",
-                        page, pj, j
-                    );
-                    update_idx = Some(j);
-                }
-            }
-            if let Some(update_idx) = update_idx {
-                #[cfg(test)]
-                println!("  Moving {page} at {i} to {update_idx}, before: {out:?}");
-                out.remove(i);
-                out.insert(update_idx, page);
-                #[cfg(test)]
-                println!("  After {out:?}");
-            } else {
-                i += 1
-            }
+        let mut graph = rules.subgraph(|page| update.contains(page));
+        for &page in update {
+            graph.add_node(page);
         }
+        let order = graph
+            .toposort()
+            .expect("Rules between pages of one update are never cyclic");
 
-        Some(out)
+        Some(order.into_iter().map(|id| *graph.node(id)).collect())
     }
 
     #[cfg(test)]
@@ -152,20 +111,16 @@ mod part2 {
 
         #[test]
         fn test_example() {
-            let input = aoc::example::example_string("day5.txt");
-            let input = parse_input(&input);
-            println!("{:?}", input.rules_after);
+            let input = aoc::example::example_input("day5.txt");
+            let input = parse_input(&input).unwrap();
 
             assert_eq!(
-                fix(&[75, 97, 47, 61, 53], &input.rules_after).unwrap(),
+                fix(&[75, 97, 47, 61, 53], &input.rules).unwrap(),
                 vec![97, 75, 47, 61, 53]
             );
+            assert_eq!(fix(&[61, 13, 29], &input.rules).unwrap(), vec![61, 29, 13]);
             assert_eq!(
-                fix(&[61, 13, 29], &input.rules_after).unwrap(),
-                vec![61, 29, 13]
-            );
-            assert_eq!(
-                fix(&[97, 13, 75, 29, 47], &input.rules_after).unwrap(),
+                fix(&[97, 13, 75, 29, 47], &input.rules).unwrap(),
                 vec![97, 75, 47, 29, 13]
             );
 
@@ -177,8 +132,7 @@ mod part2 {
 fn main() {
     let cli = aoc::cli::parse();
 
-    let input = cli.input_string();
-    let input = parse_input(&input);
+    let input = parse_input(&cli.input()).unwrap_or_else(|e| panic!("{e}"));
 
     println!("Part 1: {}", part1::calculate(&input));
     println!("Part 2: {}", part2::calculate(&input)); // 4598
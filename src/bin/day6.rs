@@ -4,8 +4,14 @@ use std::fmt::Display;
 
 use aoc2024::aoc::{
     self,
-    grid::{Direction, Grid},
+    grid::{
+        bitgrid::{BitGrid, PointSet},
+        renderer::Style,
+        Direction, Grid,
+    },
+    player::Player,
 };
+use colored::Color;
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 enum Tile {
@@ -88,20 +94,20 @@ fn parse_input(input: &str) -> (Map, Point, Direction) {
     (map, point, direction)
 }
 
-fn walk(map: &Map, start: &Point, direction: &Direction) -> Option<HashSet<Point>> {
+fn walk(map: &Map, start: &Point, direction: &Direction) -> Option<PointSet> {
     let mut loc = *start;
     let mut direction = *direction;
-    let mut visited: HashSet<(Point, Direction)> = HashSet::new();
+    let mut visited = BitGrid::directional(map.width(), map.height());
 
     while map.in_bounds_point(&loc) {
-        visited.insert((loc, direction));
+        visited.set_direction(&loc, &direction);
         let Some(cell) = map.cell_at_point(&loc).and_then(|cell| cell.go(&direction)) else {
             break;
         };
 
         if cell.value().is_obstacle() {
             direction = direction.turn_right();
-            if visited.contains(&(loc, direction)) {
+            if visited.test_direction(&loc, &direction) {
                 return None;
             }
             continue;
@@ -110,7 +116,57 @@ fn walk(map: &Map, start: &Point, direction: &Direction) -> Option<HashSet<Point
         }
     }
 
-    Some(visited.into_iter().map(|(point, _)| point).collect())
+    Some(visited.into())
+}
+
+#[derive(Clone)]
+struct Guard {
+    point: Point,
+    direction: Direction,
+    visited: HashSet<Point>,
+}
+
+fn play(input: &str) -> std::io::Result<()> {
+    let (map, point, direction) = parse_input(input);
+    let guard = Guard {
+        point,
+        direction,
+        visited: HashSet::from([point]),
+    };
+
+    let step = |guard: &mut Guard| {
+        let Some(cell) = map
+            .cell_at_point(&guard.point)
+            .and_then(|cell| cell.go(&guard.direction))
+        else {
+            return false;
+        };
+        if cell.value().is_obstacle() {
+            guard.direction = guard.direction.turn_right();
+        } else {
+            guard.point = cell.point();
+            guard.visited.insert(guard.point);
+        }
+        true
+    };
+
+    let render = |guard: &Guard| {
+        map.render()
+            .style(|tile| match tile {
+                Tile::Obstacle => Style::fg(Color::Red),
+                _ => Style::fg(Color::BrightBlack),
+            })
+            .points(&guard.visited, Style::bg(Color::Blue))
+            .mark(
+                [guard.point],
+                guard.direction.char(),
+                Style::fg(Color::Yellow).bold(),
+            )
+            .to_string()
+    };
+
+    Player::new(guard, step, render).play()?;
+    Ok(())
 }
 
 mod part1 {
@@ -153,7 +209,7 @@ mod part2 {
         let mut cycles = 0;
         let mut i = 0;
 
-        for point in initial_walk {
+        for point in initial_walk.iter() {
             i += 1;
             println!("Cell {i}");
             let mut cell = map
@@ -193,6 +249,11 @@ fn main() {
 
     let input = cli.input_string();
 
+    if cli.play {
+        play(&input).expect("Could not play the guard's walk");
+        return;
+    }
+
     println!("Part 1: {}", part1::calculate(&input));
     println!("Part 2: {}", part2::calculate(&input)); // 1789
 }
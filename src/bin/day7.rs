@@ -1,54 +1,110 @@
-use std::iter;
+use aoc2024::{
+    aoc,
+    quant::{digit_count, split_digits_at},
+};
+use aoc_macros::AocParse;
+use rayon::prelude::*;
+
+// What a can be, given apply(a, b) == result
+#[derive(Clone, Copy)]
+enum Undo {
+    Impossible,
+    Value(usize),
+    // As with a * 0 == 0
+    Any,
+}
 
-use aoc2024::aoc;
-use itertools::Itertools;
+impl From<Option<usize>> for Undo {
+    fn from(a: Option<usize>) -> Self {
+        a.map_or(Undo::Impossible, Undo::Value)
+    }
+}
 
-type Op = fn(usize, usize) -> usize;
+// An operator along with its inverse. apply gives None on overflow, and undo gives
+// Impossible when there's no answer, which is what prunes the search.
+#[derive(Clone, Copy)]
+struct Op {
+    apply: fn(usize, usize) -> Option<usize>,
+    undo: fn(usize, usize) -> Undo,
+}
 
+const ADD: Op = Op {
+    apply: usize::checked_add,
+    undo: |result, b| result.checked_sub(b).into(),
+};
+
+const MUL: Op = Op {
+    apply: usize::checked_mul,
+    undo: |result, b| match (result, b) {
+        (0, 0) => Undo::Any,
+        (_, 0) => Undo::Impossible,
+        _ => result.is_multiple_of(b).then(|| result / b).into(),
+    },
+};
+
+const CONCAT: Op = Op {
+    apply: |a, b| {
+        a.checked_mul(10usize.checked_pow(digit_count(b))?)?
+            .checked_add(b)
+    },
+    undo: |result, b| {
+        let (head, tail) = split_digits_at(result, digit_count(b));
+        (tail == b && result >= b).then_some(head).into()
+    },
+};
+
+#[derive(AocParse)]
+#[aoc(format = "{}: {}")]
 struct Equation {
     result: usize,
     args: Vec<usize>,
 }
 
 impl Equation {
-    pub fn possible(&self, ops: &'static [Op]) -> bool {
-        self.evaluate(ops).contains(&self.result)
-    }
-
-    pub fn evaluate(&self, ops: &'static [Op]) -> impl Iterator<Item = usize> + '_ {
-        evaluate(&self.args, ops)
+    pub fn possible(&self, ops: &[Op]) -> bool {
+        possible(self.result, &self.args, ops)
     }
 }
 
-fn add(a: usize, b: usize) -> usize {
-    a + b
-}
-fn mul(a: usize, b: usize) -> usize {
-    a * b
-}
-fn concat(a: usize, b: usize) -> usize {
-    a * 10usize.pow(b.ilog10() + 1) + b
+// Works from the last argument back, undoing each operator. Undoing + or * or || only
+// works when the target is large enough, divisible, or ends in the right digits, so most
+// branches stop after an argument or two. When any value will do, all that's left is
+// whether the rest can make one without overflowing.
+fn possible(target: usize, args: &[usize], ops: &[Op]) -> bool {
+    match args {
+        [] => false,
+        [x] => *x == target,
+        [head @ .., x] => ops.iter().any(|op| match (op.undo)(target, *x) {
+            Undo::Impossible => false,
+            Undo::Value(target) => possible(target, head, ops),
+            Undo::Any => evaluate(head, ops).next().is_some(),
+        }),
+    }
 }
 
-fn evaluate<'a>(args: &'a [usize], ops: &'static [Op]) -> Box<dyn Iterator<Item = usize> + 'a> {
+// Every value the arguments can make left to right, skipping any that overflow
+fn evaluate<'a>(args: &'a [usize], ops: &'a [Op]) -> Box<dyn Iterator<Item = usize> + 'a> {
     match args {
-        [] => Box::new(iter::empty()),
-        [x] => Box::new(iter::once(*x)),
-        [head @ .., x] => {
-            Box::new(evaluate(head, ops).flat_map(move |n| ops.iter().map(move |op| op(n, *x))))
-        }
+        [] => Box::new(std::iter::empty()),
+        [x] => Box::new(std::iter::once(*x)),
+        [head @ .., x] => Box::new(
+            evaluate(head, ops)
+                .flat_map(move |n| ops.iter().filter_map(move |op| (op.apply)(n, *x))),
+        ),
     }
 }
 
+fn total_possible(equations: &[Equation], ops: &[Op]) -> usize {
+    equations
+        .par_iter()
+        .filter(|e| e.possible(ops))
+        .map(|e| e.result)
+        .sum()
+}
+
 fn parse_input(s: &str) -> Vec<Equation> {
     s.lines()
-        .map(|l| {
-            let (result, args) = l.split_once(": ").unwrap();
-            let result = result.parse().unwrap();
-            let args = args.split(' ').map(|s| s.parse().unwrap()).collect();
-
-            Equation { result, args }
-        })
+        .map(|l| l.parse().unwrap_or_else(|e| panic!("{e}")))
         .collect()
 }
 
@@ -56,10 +112,7 @@ mod part1 {
     use super::*;
 
     pub fn calculate(equations: &[Equation]) -> usize {
-        equations
-            .iter()
-            .filter_map(|e| e.possible(&[add, mul]).then_some(e.result))
-            .sum()
+        total_possible(equations, &[ADD, MUL])
     }
 
     #[cfg(test)]
@@ -69,7 +122,7 @@ mod part1 {
         #[test]
         fn test_example() {
             let input = aoc::example::example_string("day7.txt");
-            for val in evaluate(&[81, 40, 27], &[add, mul]) {
+            for val in evaluate(&[81, 40, 27], &[ADD, MUL]) {
                 println!("{val}");
             }
             assert_eq!(calculate(&parse_input(&input)), 3749);
@@ -81,10 +134,7 @@ mod part2 {
     use super::*;
 
     pub fn calculate(equations: &[Equation]) -> usize {
-        equations
-            .iter()
-            .filter_map(|e| e.possible(&[add, mul, concat]).then_some(e.result))
-            .sum()
+        total_possible(equations, &[ADD, MUL, CONCAT])
     }
 
     #[cfg(test)]
@@ -108,3 +158,38 @@ fn main() {
     println!("Part 1: {}", part1::calculate(&equations));
     println!("Part 2: {}", part2::calculate(&equations));
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_matches_evaluate() {
+        let input = aoc::example::example_string("day7.txt");
+        let xor = Op {
+            apply: |a, b| Some(a ^ b),
+            undo: |result, b| Undo::Value(result ^ b),
+        };
+        let zeros = [
+            (0, vec![5, 0]),
+            (3, vec![0, 0, 3]),
+            (0, vec![7, 0, 0]),
+            (0, vec![usize::MAX, 2, 0]),
+        ];
+        let equations = parse_input(&input)
+            .into_iter()
+            .chain(zeros.map(|(result, args)| Equation { result, args }));
+
+        for e in equations {
+            for ops in [&[ADD, MUL][..], &[ADD, MUL, CONCAT], &[MUL, xor]] {
+                assert_eq!(
+                    e.possible(ops),
+                    evaluate(&e.args, ops).any(|n| n == e.result)
+                );
+            }
+        }
+        assert!(possible(0, &[5, 0], &[MUL]));
+        assert!(possible(13, &[6, 11], &[xor]));
+        assert!(!possible(12, &[4, 5], &[CONCAT]));
+    }
+}
@@ -1,44 +1,30 @@
-use aoc2024::{aoc, point2d::Point2D};
+use aoc2024::{aoc, aoc::grid::Grid, point2d::Point2Disize};
 use std::collections::{HashMap, HashSet};
 
-#[allow(clippy::type_complexity)]
-fn parse_input(
-    input: &str,
-) -> (
-    impl Fn(&Point2D<isize>) -> bool,
-    HashMap<char, Vec<Point2D<isize>>>,
-) {
-    let width = input.lines().next().unwrap().len() as isize;
-    let height = input.lines().count() as isize;
-
-    let frequency_points: HashMap<char, Vec<Point2D<isize>>> = input
-        .lines()
-        .enumerate()
-        .flat_map(|(y, line)| {
-            let y = y as isize;
-            line.chars().enumerate().filter_map(move |(x, c)| {
-                let x = x as isize;
-                (c != '.').then_some((c, Point2D::new(x, y)))
-            })
-        })
-        .fold(HashMap::new(), |mut acc, (c, point)| {
-            acc.entry(c).or_default().push(point);
+fn parse_input(input: &str) -> (Grid<Vec<char>>, HashMap<char, Vec<Point2Disize>>) {
+    let grid: Grid<Vec<char>> = input.parse().unwrap();
+
+    let frequency_points = grid.iter().filter(|cell| *cell.value() != '.').fold(
+        HashMap::new(),
+        |mut acc: HashMap<_, Vec<_>>, cell| {
+            acc.entry(*cell.value())
+                .or_default()
+                .push(Point2Disize::from(cell.point()));
             acc
-        });
+        },
+    );
 
-    (
-        move |p: &Point2D<isize>| p.x >= 0 && p.x < width && p.y >= 0 && p.y < height,
-        frequency_points,
-    )
+    (grid, frequency_points)
 }
 
 mod part1 {
     use super::*;
 
     pub fn calculate(input: &str) -> usize {
-        let (in_bounds, frequency_points) = parse_input(input);
+        let (grid, frequency_points) = parse_input(input);
+        let in_bounds = |p: &Point2Disize| grid.checked_point(*p).is_some();
 
-        let mut antinodes: HashSet<Point2D<isize>> = HashSet::new();
+        let mut antinodes: HashSet<Point2Disize> = HashSet::new();
 
         for (_, points) in frequency_points {
             for i in 0..points.len() - 1 {
@@ -74,9 +60,10 @@ mod part2 {
     use super::*;
 
     pub fn calculate(input: &str) -> usize {
-        let (in_bounds, frequency_points) = parse_input(input);
+        let (grid, frequency_points) = parse_input(input);
+        let in_bounds = |p: &Point2Disize| grid.checked_point(*p).is_some();
 
-        let mut antinodes: HashSet<Point2D<isize>> = HashSet::new();
+        let mut antinodes: HashSet<Point2Disize> = HashSet::new();
 
         for (_, points) in frequency_points {
             for i in 0..points.len() - 1 {
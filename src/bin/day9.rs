@@ -4,7 +4,7 @@ use std::{
     str::FromStr,
 };
 
-use aoc2024::aoc;
+use aoc2024::aoc::{self, interval::IntervalSet};
 
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
 struct EntryDisk(Vec<Entry>);
@@ -26,34 +26,39 @@ impl EntryDisk {
     }
 
     pub fn pack_whole_files(&mut self) {
-        let mut i = 0;
+        let mut files = vec![];
+        let mut free = IntervalSet::new();
+        let mut offset = 0;
 
-        while i < self.len() {
-            let free_size = match self[i] {
-                Entry::Free(size) if size > 0 => size,
-                _ => {
-                    i += 1;
-                    continue;
-                }
-            };
-            #[cfg(test)]
-            {
-                println!("{}", self);
-                println!("Trying to replace free size={} at index {}", free_size, i);
+        for entry in &self.0 {
+            let range = offset..offset + entry.size();
+            match *entry {
+                Entry::File { id, .. } => files.push((id, range.clone())),
+                Entry::Free(_) => free.insert(range.clone()),
             }
-            for j in (i + 1..self.len()).rev() {
-                match self[j] {
-                    Entry::File { id: _, size } if 0 < size && size <= free_size => {
-                        self[i].set_size(free_size - size);
-                        let to_insert = self[j];
-                        self[j] = Entry::free(size);
-                        self.0.insert(i, to_insert);
-                        break;
-                    }
-                    _ => {}
-                }
+            offset = range.end;
+        }
+
+        // Each file moves at most once, to the first gap it fits in to its left
+        for (_, file) in files.iter_mut().rev() {
+            let size = file.end - file.start;
+            if let Some(gap) = free.first_fit(size, Some(file.start)) {
+                let moved = gap.start..gap.start + size;
+                free.remove(moved.clone());
+                free.insert(file.clone());
+                *file = moved;
+            }
+        }
+
+        files.sort_by_key(|(_, range)| range.start);
+        self.0.clear();
+        let mut offset = 0;
+        for (id, range) in files {
+            if range.start > offset {
+                self.0.push(Entry::free(range.start - offset));
             }
-            i += 1;
+            self.0.push(Entry::file(id, range.end - range.start));
+            offset = range.end;
         }
     }
 
@@ -193,12 +198,6 @@ impl Entry {
             Entry::Free(size) => *size,
         }
     }
-    pub fn set_size(&mut self, size: usize) {
-        match self {
-            Entry::File { id: _, size: s } => *s = size,
-            Entry::Free(s) => *s = size,
-        }
-    }
 }
 
 type Id = u32;
@@ -1,3 +1,6 @@
+// So #[derive(AocParse)], which names ::aoc2024, works inside this crate too
+extern crate self as aoc2024;
+
 pub mod aoc;
 pub mod point2d;
 pub mod quant;
@@ -1,8 +1,16 @@
-use std::fmt::Display;
+use std::{
+    fmt::Display,
+    num::TryFromIntError,
+    ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign},
+};
 
-use crate::quant::{NumConsts, RemEuclid};
+use crate::{
+    aoc::grid::{Direction, Point},
+    quant::{Integer, NumConsts, RemEuclid},
+};
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+// Ordered by x, then y
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Point2D<N> {
     pub x: N,
     pub y: N,
@@ -18,7 +26,7 @@ where
 }
 
 impl<N> Point2D<N> {
-    pub fn new(x: N, y: N) -> Self {
+    pub const fn new(x: N, y: N) -> Self {
         Point2D { x, y }
     }
 
@@ -72,11 +80,82 @@ where
     pub const fn one() -> Self {
         Point2D {
             x: N::ONE,
-            y: N::ZERO,
+            y: N::ONE,
         }
     }
 }
 
+impl<N> Point2D<N>
+where
+    N: Copy + Add<Output = N> + Sub<Output = N> + Mul<Output = N>,
+{
+    pub fn dot(&self, other: &Self) -> N {
+        self.x * other.x + self.y * other.y
+    }
+
+    // z component of the 3D cross product, positive when other is clockwise
+    // from self on screen (y pointing down)
+    pub fn cross(&self, other: &Self) -> N {
+        self.x * other.y - self.y * other.x
+    }
+}
+
+fn abs_diff<N: Integer>(a: N, b: N) -> N {
+    if a > b {
+        a - b
+    } else {
+        b - a
+    }
+}
+
+impl<N: Integer> Point2D<N> {
+    pub fn abs(&self) -> Self {
+        Self::new(abs_diff(self.x, N::ZERO), abs_diff(self.y, N::ZERO))
+    }
+
+    pub fn manhattan(&self) -> N {
+        self.abs().sum()
+    }
+
+    pub fn chebyshev(&self) -> N {
+        let abs = self.abs();
+        abs.x.max(abs.y)
+    }
+
+    // Safe for unsigned points too, unlike (other - self).manhattan()
+    pub fn manhattan_distance(&self, other: &Self) -> N {
+        abs_diff(self.x, other.x) + abs_diff(self.y, other.y)
+    }
+
+    pub fn chebyshev_distance(&self, other: &Self) -> N {
+        abs_diff(self.x, other.x).max(abs_diff(self.y, other.y))
+    }
+}
+
+impl<N> Point2D<N>
+where
+    N: Copy + TryInto<usize>,
+{
+    // Checked conversion to a grid index, None if either coordinate is negative or too big
+    pub fn to_point(&self) -> Option<Point> {
+        Some(Point::new(self.x.try_into().ok()?, self.y.try_into().ok()?))
+    }
+}
+
+impl<N> Point2D<N>
+where
+    N: Copy + Neg<Output = N>,
+{
+    // A quarter turn clockwise on screen (y pointing down), so up becomes right
+    pub fn rotate90(&self) -> Self {
+        Self::new(-self.y, self.x)
+    }
+
+    pub fn rotate90_ccw(&self) -> Self {
+        Self::new(self.y, -self.x)
+    }
+}
+
 impl<N, T> std::ops::Add for Point2D<N>
 where
     N: std::ops::Add<Output = T>,
@@ -156,5 +235,126 @@ where
     }
 }
 
+impl<N, T> Neg for Point2D<N>
+where
+    N: Neg<Output = T>,
+{
+    type Output = Point2D<T>;
+
+    fn neg(self) -> Self::Output {
+        Point2D::new(-self.x, -self.y)
+    }
+}
+
+impl<N: AddAssign> AddAssign for Point2D<N> {
+    fn add_assign(&mut self, rhs: Self) {
+        self.x += rhs.x;
+        self.y += rhs.y;
+    }
+}
+
+impl<N: SubAssign> SubAssign for Point2D<N> {
+    fn sub_assign(&mut self, rhs: Self) {
+        self.x -= rhs.x;
+        self.y -= rhs.y;
+    }
+}
+
 pub type Point2Disize = Point2D<isize>;
-pub type Point2Dusize = Point2D<isize>;
+pub type Point2Dusize = Point2D<usize>;
+
+// Grids are never big enough for this cast to wrap
+impl From<Point> for Point2Disize {
+    fn from(point: Point) -> Self {
+        Self::new(point.x as isize, point.y as isize)
+    }
+}
+
+// Fails for points left of or above the grid
+impl TryFrom<Point2Disize> for Point {
+    type Error = TryFromIntError;
+
+    fn try_from(point: Point2Disize) -> Result<Self, Self::Error> {
+        Ok(Point::new(point.x.try_into()?, point.y.try_into()?))
+    }
+}
+
+// Unit vectors with y pointing down, so up is (0, -1)
+impl From<Direction> for Point2Disize {
+    fn from(direction: Direction) -> Self {
+        match direction {
+            Direction::Up => Self::new(0, -1),
+            Direction::Down => Self::new(0, 1),
+            Direction::Left => Self::new(-1, 0),
+            Direction::Right => Self::new(1, 0),
+        }
+    }
+}
+
+impl TryFrom<Point2Disize> for Direction {
+    type Error = &'static str;
+
+    fn try_from(point: Point2Disize) -> Result<Self, Self::Error> {
+        Direction::all()
+            .iter()
+            .copied()
+            .find(|&d| Point2Disize::from(d) == point)
+            .ok_or("Not a unit vector along an axis")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_products() {
+        let a = Point2Disize::new(3, -4);
+        let b = Point2Disize::new(2, 5);
+        assert_eq!(a.dot(&b), -14);
+        assert_eq!(a.cross(&b), 23);
+        assert_eq!(-a, Point2Disize::new(-3, 4));
+        assert_eq!(Point2Disize::one(), Point2Disize::new(1, 1));
+    }
+
+    #[test]
+    fn test_norms() {
+        let a = Point2Disize::new(3, -4);
+        assert_eq!(a.abs(), Point2Disize::new(3, 4));
+        assert_eq!(a.manhattan(), 7);
+        assert_eq!(a.chebyshev(), 4);
+        let (p, q) = (Point2Dusize::new(1, 9), Point2Dusize::new(4, 2));
+        assert_eq!(p.manhattan_distance(&q), 10);
+        assert_eq!(p.chebyshev_distance(&q), 7);
+    }
+
+    #[test]
+    fn test_rotate() {
+        let up = Point2Disize::from(Direction::Up);
+        assert_eq!(Direction::try_from(up.rotate90()), Ok(Direction::Right));
+        assert_eq!(up.rotate90().rotate90_ccw(), up);
+        assert_eq!(
+            Direction::try_from(Point2Disize::new(1, 1)),
+            Err("Not a unit vector along an axis")
+        );
+
+        let mut p = Point2Disize::from(Point::new(2, 3));
+        p += up;
+        p -= Point2Disize::new(3, 0);
+        assert_eq!(Point::try_from(p + (1, 0)), Ok(Point::new(0, 2)));
+        assert!(Point::try_from(p).is_err());
+        assert!(Point2Disize::new(0, 5) < Point2Disize::new(1, 0));
+    }
+
+    #[test]
+    fn test_grid_point() {
+        let p = Point2Disize::new(3, 1);
+        assert_eq!(p.to_point(), Some(Point::new(3, 1)));
+        assert_eq!((-p).to_point(), None);
+
+        let grid = crate::aoc::grid::Grid::fill(3, 2, '.');
+        assert_eq!(grid.checked_point(p), None);
+        assert_eq!(grid.checked_point(p - (1, 0)), Some(Point::new(2, 1)));
+        assert_eq!(Point::new(1, 2) + Point::new(3, 4), Point::new(4, 6));
+    }
+}
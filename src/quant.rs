@@ -1,32 +1,418 @@
-use std::ops::{Div, Mul, Rem};
+use std::{
+    fmt::Display,
+    hash::Hash,
+    ops::{Add, Div, Mul, Neg, Range, Rem, Shl, Shr, Sub},
+};
 
-pub fn lcm<T>(a: T, b: T) -> T
+// Binary gcd, always non-negative. None only when the answer doesn't fit, which
+// can only happen with a signed MIN and zero or another MIN.
+pub fn checked_gcd<T: Integer>(a: T, b: T) -> Option<T> {
+    // MIN has no positive counterpart, so take a Euclid step to get it out of the way
+    let (mut a, mut b) = match (a.checked_abs(), b.checked_abs()) {
+        (Some(a), Some(b)) => (a, b),
+        (None, Some(b)) if b != T::ZERO => (b, (a % b).checked_abs()?),
+        (Some(a), None) if a != T::ZERO => (a, (b % a).checked_abs()?),
+        _ => return None,
+    };
+    if a == T::ZERO || b == T::ZERO {
+        return Some(a.max(b));
+    }
+
+    let shift = a.trailing_zeros().min(b.trailing_zeros());
+    a = a >> a.trailing_zeros();
+    loop {
+        b = b >> b.trailing_zeros();
+        if a > b {
+            (a, b) = (b, a);
+        }
+        b = b - a;
+        if b == T::ZERO {
+            return Some(a << shift);
+        }
+    }
+}
+
+pub fn gcd<T: Integer>(a: T, b: T) -> T {
+    checked_gcd(a, b).expect("gcd doesn't fit in its type")
+}
+
+// Divides before multiplying, so this only fails if the lcm itself doesn't fit
+pub fn checked_lcm<T: Integer>(a: T, b: T) -> Option<T> {
+    if a == T::ZERO || b == T::ZERO {
+        return Some(T::ZERO);
+    }
+    (a / checked_gcd(a, b)?).checked_mul(b)?.checked_abs()
+}
+
+pub fn lcm<T: Integer>(a: T, b: T) -> T {
+    checked_lcm(a, b).expect("lcm doesn't fit in its type")
+}
+
+// (g, x, y) such that a * x + b * y = g = gcd(a, b). Signed only, since x or y is
+// negative whenever neither of a and b divides the other.
+pub fn ext_gcd<T: Signed>(a: T, b: T) -> (T, T, T) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (T::ONE, T::ZERO);
+    let (mut old_y, mut y) = (T::ZERO, T::ONE);
+
+    while r != T::ZERO {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+
+    (old_r, old_x, old_y)
+}
+
+// Works in i128, so unsigned types are fine too. None if there's no inverse, or m
+// doesn't fit in an i128.
+pub fn mod_inverse<T: Integer>(a: T, m: T) -> Option<T> {
+    let m = m.to_i128()?;
+    let (g, x, _) = ext_gcd(a.to_i128()?.rem_euclid(m), m);
+    (g == 1).then(|| T::from_i128(x.rem_euclid(m))).flatten()
+}
+
+// Multiplies through u128 like mul_mod, so any modulus up to u64::MAX works whatever T is.
+// Panics on a negative exp, take the mod_inverse of base first for those.
+pub fn mod_pow<T: Integer>(base: T, mut exp: T, m: T) -> T {
+    assert!(
+        exp >= T::ZERO,
+        "mod_pow needs a non-negative exponent, not {exp}"
+    );
+    let two = T::ONE + T::ONE;
+    let mut base = base.rem_euclid(m).to_i128().unwrap() as u64;
+    let m = m
+        .to_i128()
+        .and_then(|m| u64::try_from(m).ok())
+        .expect("mod_pow needs a modulus up to u64::MAX");
+    let mut result = 1 % m;
+
+    while exp > T::ZERO {
+        if exp % two == T::ONE {
+            result = mul_mod(result, base, m);
+        }
+        base = mul_mod(base, base, m);
+        exp = exp / two;
+    }
+
+    T::from_i128(result.into()).unwrap()
+}
+
+// Smallest non-negative x with x = r (mod m) for every (r, m), along with the
+// lcm of the moduli. Moduli needn't be coprime. Works in i128 whatever T is, and is None
+// if the congruences disagree or the answer doesn't fit.
+pub fn crt<T: Integer>(congruences: impl IntoIterator<Item = (T, T)>) -> Option<(T, T)> {
+    let (r, m) = congruences
+        .into_iter()
+        .try_fold((0i128, 1i128), |(r1, m1), (r2, m2)| {
+            let (r2, m2) = (r2.to_i128()?, m2.to_i128()?);
+            let (g, p, _) = ext_gcd(m1, m2);
+            let diff = r2.rem_euclid(m2) - r1.rem_euclid(m2);
+            if diff % g != 0 {
+                return None;
+            }
+            let step = m2 / g;
+            let k = (diff / g)
+                .rem_euclid(step)
+                .checked_mul(p.rem_euclid(step))?
+                % step;
+            let modulus = m1.checked_mul(step)?;
+            Some(((r1 + m1 * k).rem_euclid(modulus), modulus))
+        })?;
+    Some((T::from_i128(r)?, T::from_i128(m)?))
+}
+
+fn ten<T: NumConsts + Add<Output = T> + Copy>() -> T {
+    let two = T::ONE + T::ONE;
+    let five = two + two + T::ONE;
+    five + five
+}
+
+pub fn pow10<T>(exp: u32) -> T
 where
-    T: Mul<Output = T> + Div<Output = T> + Eq + NumConsts + Rem<Output = T> + Copy,
+    T: NumConsts + Add<Output = T> + Mul<Output = T> + Copy,
 {
-    a * b / gcd(a, b)
+    (0..exp).fold(T::ONE, |acc, _| acc * ten())
 }
 
-pub fn gcd<T>(a: T, b: T) -> T
+// Number of decimal digits, ignoring any sign, with 0 having one digit
+pub fn digit_count<T>(mut n: T) -> u32
 where
-    T: Rem<Output = T> + Eq + NumConsts + Copy,
+    T: NumConsts + Add<Output = T> + Div<Output = T> + Eq + Copy,
 {
-    if b == T::ZERO {
-        a
-    } else {
-        gcd(b, a % b)
+    let mut count = 1;
+    while n / ten() != T::ZERO {
+        n = n / ten();
+        count += 1;
+    }
+    count
+}
+
+// Splits off the lowest `digits` digits, so split_digits_at(1234, 1) is (123, 4)
+pub fn split_digits_at<T>(n: T, digits: u32) -> (T, T)
+where
+    T: NumConsts + Add<Output = T> + Mul<Output = T> + Div<Output = T> + Rem<Output = T> + Copy,
+{
+    let div = pow10(digits);
+    (n / div, n % div)
+}
+
+// The digits of a followed by the digits of b, so concat_digits(12, 345) is 12345
+pub fn concat_digits<T>(a: T, b: T) -> T
+where
+    T: NumConsts + Add<Output = T> + Mul<Output = T> + Div<Output = T> + Eq + Copy,
+{
+    a * pow10(digit_count(b)) + b
+}
+
+// Primes up to and including limit
+pub fn sieve(limit: usize) -> Vec<usize> {
+    if limit < 2 {
+        return vec![];
+    }
+    let mut composite = vec![false; limit + 1];
+    let mut primes = vec![];
+    for n in 2..=limit {
+        if composite[n] {
+            continue;
+        }
+        primes.push(n);
+        for multiple in (n * n..=limit).step_by(n) {
+            composite[multiple] = true;
+        }
+    }
+    primes
+}
+
+const SEGMENT_SIZE: u64 = 1 << 16;
+
+// Primes in the range, sieved a segment at a time so memory stays bounded
+// by sqrt(range.end) rather than range.end
+pub fn sieve_range(range: Range<u64>) -> Vec<u64> {
+    let start = range.start.max(2);
+    if start >= range.end {
+        return vec![];
+    }
+    let base = sieve((range.end - 1).isqrt() as usize);
+    let mut primes = vec![];
+
+    for low in (start..range.end).step_by(SEGMENT_SIZE as usize) {
+        let high = (low + SEGMENT_SIZE).min(range.end);
+        let mut composite = vec![false; (high - low) as usize];
+        for &p in &base {
+            let p = p as u64;
+            let first = (p * p).max(low.div_ceil(p) * p);
+            for multiple in (first..high).step_by(p as usize) {
+                composite[(multiple - low) as usize] = true;
+            }
+        }
+        primes.extend((low..high).filter(|&n| !composite[(n - low) as usize]));
+    }
+
+    primes
+}
+
+fn mul_mod(a: u64, b: u64, m: u64) -> u64 {
+    (a as u128 * b as u128 % m as u128) as u64
+}
+
+// Deterministic Miller-Rabin, these bases are enough for every u64
+pub fn is_prime(n: u64) -> bool {
+    const BASES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+    if n < 2 {
+        return false;
+    }
+    if let Some(&p) = BASES.iter().find(|&&p| n.is_multiple_of(p)) {
+        return n == p;
+    }
+
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+    BASES.iter().all(|&a| {
+        let mut x = mod_pow(a, d, n);
+        if x == 1 || x == n - 1 {
+            return true;
+        }
+        (1..s).any(|_| {
+            x = mul_mod(x, x, n);
+            x == n - 1
+        })
+    })
+}
+
+// Some non-trivial factor of an odd composite n
+fn pollard_rho(n: u64) -> u64 {
+    for c in 1.. {
+        let f = |x: u64| ((x as u128 * x as u128 + c as u128) % n as u128) as u64;
+        let (mut x, mut y, mut d) = (2, 2, 1);
+        while d == 1 {
+            x = f(x);
+            y = f(f(y));
+            d = gcd(x.abs_diff(y), n);
+        }
+        if d != n {
+            return d;
+        }
+    }
+    unreachable!()
+}
+
+// Prime factors with their multiplicities, smallest prime first
+pub fn factorize(n: u64) -> Vec<(u64, u32)> {
+    fn collect(n: u64, out: &mut Vec<u64>) {
+        if n == 1 {
+            return;
+        }
+        if is_prime(n) {
+            out.push(n);
+            return;
+        }
+        let d = pollard_rho(n);
+        collect(d, out);
+        collect(n / d, out);
+    }
+
+    if n == 0 {
+        return vec![];
+    }
+    let mut n = n;
+    let mut primes = vec![];
+    for p in [2, 3, 5, 7] {
+        while n > 1 && n.is_multiple_of(p) {
+            primes.push(p);
+            n /= p;
+        }
+    }
+    collect(n, &mut primes);
+    primes.sort_unstable();
+
+    primes
+        .chunk_by(|a, b| a == b)
+        .map(|run| (run[0], run.len() as u32))
+        .collect()
+}
+
+pub fn divisors(n: u64) -> Vec<u64> {
+    if n == 0 {
+        return vec![];
+    }
+    let mut out = vec![1];
+    for (p, k) in factorize(n) {
+        let powers: Vec<u64> = (1..=k)
+            .scan(1, |acc, _| {
+                *acc *= p;
+                Some(*acc)
+            })
+            .collect();
+        out = out
+            .iter()
+            .flat_map(|&d| std::iter::once(d).chain(powers.iter().map(move |&pk| d * pk)))
+            .collect();
+    }
+    out.sort_unstable();
+    out
+}
+
+// Count of 1..=n coprime to n
+pub fn totient(n: u64) -> u64 {
+    factorize(n)
+        .into_iter()
+        .fold(n, |acc, (p, _)| acc / p * (p - 1))
+}
+
+// An integer modulo M, always kept in 0..M
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Mod<const M: u64>(u64);
+
+impl<const M: u64> Mod<M> {
+    pub const fn new(n: u64) -> Self {
+        Self(n % M)
+    }
+
+    pub const fn value(&self) -> u64 {
+        self.0
+    }
+
+    pub fn pow(self, exp: u64) -> Self {
+        Self(mod_pow(self.0, exp, M))
+    }
+
+    pub fn inverse(self) -> Option<Self> {
+        mod_inverse(self.0, M).map(Self)
+    }
+}
+
+impl<const M: u64> NumConsts for Mod<M> {
+    const ZERO: Self = Self(0);
+    const ONE: Self = Self(1 % M);
+}
+
+impl<const M: u64> From<u64> for Mod<M> {
+    fn from(n: u64) -> Self {
+        Self::new(n)
+    }
+}
+
+impl<const M: u64> From<i64> for Mod<M> {
+    fn from(n: i64) -> Self {
+        Self((n as i128).rem_euclid(M as i128) as u64)
+    }
+}
+
+impl<const M: u64> Add for Mod<M> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self(((self.0 as u128 + rhs.0 as u128) % M as u128) as u64)
+    }
+}
+
+impl<const M: u64> Sub for Mod<M> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + -rhs
+    }
+}
+
+impl<const M: u64> Neg for Mod<M> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self((M - self.0) % M)
+    }
+}
+
+impl<const M: u64> Mul for Mod<M> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self((self.0 as u128 * rhs.0 as u128 % M as u128) as u64)
+    }
+}
+
+impl<const M: u64> Div for Mod<M> {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        let inverse = rhs
+            .inverse()
+            .unwrap_or_else(|| panic!("{} has no inverse modulo {M}", rhs.0));
+        Mul::mul(self, inverse)
+    }
+}
+
+impl<const M: u64> Display for Mod<M> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
     }
 }
 
 pub trait QuantIter: Iterator {
     fn lcm(mut self) -> Option<Self::Item>
     where
-        Self::Item: Mul<Output = Self::Item>
-            + Div<Output = Self::Item>
-            + Eq
-            + NumConsts
-            + Rem<Output = Self::Item>
-            + Copy,
+        Self::Item: Integer,
         Self: Sized,
     {
         self.next().map(|first| self.fold(first, lcm))
@@ -34,11 +420,28 @@ pub trait QuantIter: Iterator {
 
     fn gcd(mut self) -> Option<Self::Item>
     where
-        Self::Item: Rem<Output = Self::Item> + Eq + NumConsts + Copy,
+        Self::Item: Integer,
         Self: Sized,
     {
         self.next().map(|first| self.fold(first, gcd))
     }
+
+    // None on overflow rather than panicking or wrapping
+    fn checked_sum(mut self) -> Option<Self::Item>
+    where
+        Self::Item: Integer,
+        Self: Sized,
+    {
+        self.try_fold(Self::Item::ZERO, Integer::checked_add)
+    }
+
+    fn checked_product(mut self) -> Option<Self::Item>
+    where
+        Self::Item: Integer,
+        Self: Sized,
+    {
+        self.try_fold(Self::Item::ONE, Integer::checked_mul)
+    }
 }
 
 impl<T: ?Sized> QuantIter for T where T: Iterator {}
@@ -103,3 +506,240 @@ impl_rem_euclid!(i32);
 impl_rem_euclid!(i64);
 impl_rem_euclid!(i128);
 impl_rem_euclid!(isize);
+
+pub trait Integer:
+    NumConsts
+    + RemEuclid
+    + Copy
+    + Ord
+    + Hash
+    + Display
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Rem<Output = Self>
+    + Shl<u32, Output = Self>
+    + Shr<u32, Output = Self>
+{
+    const MIN: Self;
+    const MAX: Self;
+    const SIGNED: bool;
+
+    fn checked_add(self, other: Self) -> Option<Self>;
+    fn checked_sub(self, other: Self) -> Option<Self>;
+    fn checked_mul(self, other: Self) -> Option<Self>;
+    // Always Some for unsigned types
+    fn checked_abs(self) -> Option<Self>;
+    fn trailing_zeros(self) -> u32;
+    // Exact conversions, None when the value doesn't fit
+    fn to_i128(self) -> Option<i128>;
+    fn from_i128(n: i128) -> Option<Self>;
+}
+
+// Integers with negatives, for results that can be negative even from positive arguments
+pub trait Signed: Integer + Neg<Output = Self> {}
+macro_rules! impl_integer {
+    ($typ:ty, $signed:literal, $abs:expr) => {
+        impl Integer for $typ {
+            const MIN: $typ = <$typ>::MIN;
+            const MAX: $typ = <$typ>::MAX;
+            const SIGNED: bool = $signed;
+
+            fn checked_add(self, other: Self) -> Option<Self> {
+                <$typ>::checked_add(self, other)
+            }
+            fn checked_sub(self, other: Self) -> Option<Self> {
+                <$typ>::checked_sub(self, other)
+            }
+            fn checked_mul(self, other: Self) -> Option<Self> {
+                <$typ>::checked_mul(self, other)
+            }
+            fn checked_abs(self) -> Option<Self> {
+                $abs(self)
+            }
+            fn trailing_zeros(self) -> u32 {
+                <$typ>::trailing_zeros(self)
+            }
+            fn to_i128(self) -> Option<i128> {
+                i128::try_from(self).ok()
+            }
+            fn from_i128(n: i128) -> Option<Self> {
+                <$typ>::try_from(n).ok()
+            }
+        }
+    };
+    ($typ:ty, signed) => {
+        impl_integer!($typ, true, <$typ>::checked_abs);
+        impl Signed for $typ {}
+    };
+    ($typ:ty) => {
+        impl_integer!($typ, false, Some);
+    };
+}
+impl_integer!(u8);
+impl_integer!(u16);
+impl_integer!(u32);
+impl_integer!(u64);
+impl_integer!(u128);
+impl_integer!(usize);
+impl_integer!(i8, signed);
+impl_integer!(i16, signed);
+impl_integer!(i32, signed);
+impl_integer!(i64, signed);
+impl_integer!(i128, signed);
+impl_integer!(isize, signed);
+
+pub trait Float:
+    NumConsts
+    + RemEuclid
+    + Copy
+    + PartialOrd
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+{
+    const EPSILON: Self;
+
+    fn abs(self) -> Self;
+    fn sqrt(self) -> Self;
+    fn floor(self) -> Self;
+    fn ceil(self) -> Self;
+    fn round(self) -> Self;
+}
+macro_rules! impl_float {
+    ($typ:ty) => {
+        impl Float for $typ {
+            const EPSILON: $typ = <$typ>::EPSILON;
+
+            fn abs(self) -> Self {
+                <$typ>::abs(self)
+            }
+            fn sqrt(self) -> Self {
+                <$typ>::sqrt(self)
+            }
+            fn floor(self) -> Self {
+                <$typ>::floor(self)
+            }
+            fn ceil(self) -> Self {
+                <$typ>::ceil(self)
+            }
+            fn round(self) -> Self {
+                <$typ>::round(self)
+            }
+        }
+    };
+}
+impl_float!(f32);
+impl_float!(f64);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_gcd() {
+        assert_eq!(gcd(48u32, 180), 12);
+        assert_eq!(gcd(-48i32, 180), 12);
+        assert_eq!(gcd(0i64, -7), 7);
+        assert_eq!(gcd(i64::MIN, 6), 2);
+        assert_eq!(checked_gcd(i64::MIN, 0), None);
+        assert_eq!(lcm(4u8, 6), 12);
+        assert_eq!(lcm(-4i32, 6), 12);
+        assert_eq!(checked_lcm(u64::MAX, 1), Some(u64::MAX));
+        assert_eq!(checked_lcm(u64::MAX, 2), None);
+    }
+
+    #[test]
+    fn test_quant_iter() {
+        assert_eq!([4u64, 6, 10].into_iter().lcm(), Some(60));
+        assert_eq!([12i32, -18].into_iter().gcd(), Some(6));
+        assert_eq!([200u8, 55].into_iter().checked_sum(), Some(255));
+        assert_eq!([200u8, 56].into_iter().checked_sum(), None);
+        assert_eq!([16u8, 16].into_iter().checked_product(), None);
+        assert_eq!(std::iter::empty::<u8>().checked_product(), Some(1));
+    }
+
+    #[test]
+    fn test_modular() {
+        assert_eq!(ext_gcd(240i64, 46), (2, -9, 47));
+        assert_eq!(mod_inverse(3i64, 11), Some(4));
+        assert_eq!(mod_inverse(4i64, 12), None);
+        assert_eq!(mod_inverse(3u64, 7), Some(5));
+        assert_eq!(mod_inverse(-3i32, 7), Some(2));
+        assert_eq!(mod_inverse(2u128, u128::MAX), None);
+        assert_eq!(mod_pow(4u64, 13, 497), 445);
+        assert_eq!(mod_pow(7u64, 0, 1), 0);
+        assert_eq!(
+            mod_pow(999_999_999_999u64, 3, 1_000_000_000_007),
+            999_999_999_495
+        );
+        assert_eq!(
+            mod_pow(123456789u64, 10u64.pow(18), (1 << 63) + 29),
+            6841257511529448402
+        );
+        assert_eq!(mod_pow(-2i64, 3, 5), 2);
+        assert!(std::panic::catch_unwind(|| mod_pow(2i64, -1, 5)).is_err());
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(crt([(2i64, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt([(3i64, 4), (5, 6)]), Some((11, 12)));
+        assert_eq!(crt([(1i64, 4), (2, 6)]), None);
+        assert_eq!(crt([(-1i64, 101), (-1, 103)]), Some((10402, 10403)));
+        assert_eq!(crt([(2u64, 3), (3, 5)]), Some((8, 15)));
+        assert_eq!(crt([(1u8, 16), (2, 17)]), None);
+        assert_eq!(
+            crt([(1u64, 1 << 40), (0, 3)]),
+            Some(((1 << 41) + 1, 3 << 40))
+        );
+    }
+
+    #[test]
+    fn test_mod() {
+        type M7 = Mod<7>;
+        assert_eq!(M7::new(5) + M7::new(4), M7::new(2));
+        assert_eq!(M7::new(2) - M7::new(5), M7::from(-3i64));
+        assert_eq!(M7::new(3) / M7::new(5), M7::new(2));
+        assert_eq!(M7::new(3).pow(6), M7::ONE);
+        assert_eq!(Mod::<8>::new(2).inverse(), None);
+    }
+
+    #[test]
+    fn test_digits() {
+        assert_eq!(digit_count(0u32), 1);
+        assert_eq!(digit_count(-4500i64), 4);
+        assert_eq!(split_digits_at(253000usize, 3), (253, 0));
+        assert_eq!(concat_digits(12usize, 345), 12345);
+        assert_eq!(concat_digits(7usize, 0), 70);
+    }
+
+    #[test]
+    fn test_primes() {
+        assert_eq!(sieve(20), vec![2, 3, 5, 7, 11, 13, 17, 19]);
+        let big = 1_000_000..1_200_000;
+        let expected: Vec<u64> = big.clone().filter(|&n| is_prime(n)).collect();
+        assert_eq!(sieve_range(big), expected);
+        assert_eq!(sieve_range(0..10), vec![2, 3, 5, 7]);
+
+        assert!(is_prime(18446744073709551557));
+        assert!(!is_prime(3215031751));
+        assert_eq!(
+            factorize(600851475143),
+            vec![(71, 1), (839, 1), (1471, 1), (6857, 1)]
+        );
+        assert_eq!(factorize(4295098369), vec![(65537, 2)]);
+        assert_eq!(factorize(1), vec![]);
+    }
+
+    #[test]
+    fn test_divisors() {
+        assert_eq!(divisors(12), vec![1, 2, 3, 4, 6, 12]);
+        assert_eq!(divisors(1), vec![1]);
+        assert_eq!(totient(36), 12);
+        assert_eq!(totient(1), 1);
+    }
+}
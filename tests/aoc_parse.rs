@@ -0,0 +1,43 @@
+use aoc2024::{aoc::scan::ScanError, point2d::Point2D};
+use aoc_macros::AocParse;
+
+#[derive(Debug, PartialEq, AocParse)]
+#[aoc(format = "p={} v={}")]
+struct Robot {
+    position: Point2D<i32>,
+    velocity: Point2D<i32>,
+}
+
+#[derive(Debug, PartialEq, AocParse)]
+#[aoc(format = "{}: {}")]
+struct Equation(u64, Vec<u64>);
+
+#[test]
+fn test_parse() {
+    assert_eq!(
+        "p=0,4 v=3,-3".parse(),
+        Ok(Robot {
+            position: Point2D::new(0, 4),
+            velocity: Point2D::new(3, -3),
+        })
+    );
+    assert_eq!("190: 10 19".parse(), Ok(Equation(190, vec![10, 19])));
+}
+
+#[test]
+fn test_parse_errors() {
+    let column = |s: &str| s.parse::<Robot>().unwrap_err().column;
+    assert_eq!(column("q=0,4 v=3,-3"), 1);
+    assert_eq!(column("p=0,4 w=3,-3"), 3);
+    assert_eq!(column("p=0;4 v=3,-3"), 3);
+    assert_eq!(column("p=0,4 v=3,x"), 9);
+
+    let err: ScanError = "190: 10 x".parse::<Equation>().unwrap_err();
+    assert_eq!(err.column, 6);
+    assert!(err.message.contains("\"x\" is not a valid u64"), "{err}");
+}
+
+#[test]
+fn test_misuse() {
+    trybuild::TestCases::new().compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,9 @@
+use aoc_macros::AocParse;
+
+#[derive(AocParse)]
+#[aoc(format = "{}")]
+enum Tile {
+    Wall,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use aoc_macros::AocParse;
+
+#[derive(AocParse)]
+#[aoc(format = "{}|{}|{}")]
+struct Rule {
+    before: u8,
+    after: u8,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use aoc_macros::AocParse;
+
+#[derive(AocParse)]
+struct Rule {
+    before: u8,
+    after: u8,
+}
+
+fn main() {}